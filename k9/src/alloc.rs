@@ -0,0 +1,147 @@
+/*!
+Allocation counting for [assert_max_allocations!](crate::assert_max_allocations) and
+[assert_no_alloc!](crate::assert_no_alloc).
+
+Allocations can only be observed through the global allocator, so the test binary
+has to opt in by wrapping its allocator with [CountingAllocator]:
+
+```
+#[global_allocator]
+static ALLOCATOR: k9::alloc::CountingAllocator = k9::alloc::CountingAllocator::system();
+
+let (_, stats) = k9::alloc::count_allocations(|| vec![1, 2, 3]);
+k9::assert_equal!(stats.allocations, 1);
+k9::assert_equal!(stats.bytes_allocated, 12);
+```
+*/
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Allocations performed on a single thread while running a closure
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocationStats {
+    /// Number of allocations, including reallocations
+    pub allocations: usize,
+    /// Total number of bytes requested by all allocations
+    pub bytes_allocated: usize,
+    /// Highest number of bytes that were allocated at the same time
+    pub peak_bytes: usize,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Counters {
+    stats: AllocationStats,
+    /// Can go below zero if memory allocated before counting started gets freed
+    current_bytes: isize,
+}
+
+thread_local! {
+    /// `None` when allocations on this thread aren't being counted
+    static COUNTERS: Cell<Option<Counters>> = const { Cell::new(None) };
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// [GlobalAlloc] wrapper that counts allocations made inside of [count_allocations].
+/// Outside of it the overhead is a single thread local lookup.
+pub struct CountingAllocator<A = System> {
+    inner: A,
+}
+
+impl CountingAllocator<System> {
+    pub const fn system() -> Self {
+        Self { inner: System }
+    }
+}
+
+impl<A> CountingAllocator<A> {
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+}
+
+fn record(allocated: usize, freed: usize) {
+    // `try_with` since allocations can happen after thread locals are destroyed
+    let _ = COUNTERS.try_with(|counters| {
+        if let Some(mut c) = counters.get() {
+            if allocated > 0 {
+                c.stats.allocations += 1;
+                c.stats.bytes_allocated += allocated;
+            }
+            c.current_bytes += allocated as isize - freed as isize;
+            c.stats.peak_bytes = c.stats.peak_bytes.max(c.current_bytes.max(0) as usize);
+            counters.set(Some(c));
+        }
+    });
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(true, Ordering::Relaxed);
+        record(layout.size(), 0);
+        self.inner.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(true, Ordering::Relaxed);
+        record(layout.size(), 0);
+        self.inner.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(0, layout.size());
+        self.inner.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size, layout.size());
+        self.inner.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Whether [CountingAllocator] is installed as the `#[global_allocator]`
+pub fn is_installed() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// Run the closure and count allocations it made on the current thread.
+/// Allocations made by other threads are not counted.
+///
+/// The returned value is dropped by the caller, so its deallocation
+/// isn't a part of the stats.
+pub fn count_allocations<F: FnOnce() -> T, T>(f: F) -> (T, AllocationStats) {
+    struct Restore(Option<Counters>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let counters = COUNTERS.with(|counters| counters.replace(self.0));
+            // Allocations of a nested closure count towards the outer one too
+            if let (Some(outer), Some(inner)) = (self.0, counters) {
+                let peak = outer.current_bytes.max(0) as usize + inner.stats.peak_bytes;
+                COUNTERS.with(|counters| {
+                    counters.set(Some(Counters {
+                        stats: AllocationStats {
+                            allocations: outer.stats.allocations + inner.stats.allocations,
+                            bytes_allocated: outer.stats.bytes_allocated
+                                + inner.stats.bytes_allocated,
+                            peak_bytes: outer.stats.peak_bytes.max(peak),
+                        },
+                        current_bytes: outer.current_bytes + inner.current_bytes,
+                    }))
+                });
+            }
+        }
+    }
+
+    let outer = COUNTERS.with(|counters| counters.replace(Some(Counters::default())));
+    let restore = Restore(outer);
+    let result = f();
+    let stats = COUNTERS
+        .with(|counters| counters.get())
+        .unwrap_or_default()
+        .stats;
+    drop(restore);
+    (result, stats)
+}
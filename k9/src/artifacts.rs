@@ -0,0 +1,41 @@
+use anyhow::{Context, Result};
+use std::cell::Cell;
+use std::path::PathBuf;
+
+thread_local! {
+    /// Number of times artifacts were written on the current thread, so every
+    /// failure in the same test gets its own directory
+    static WRITES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Write `files` (file name and content) into `<test name>/<n>` directory
+/// inside [artifacts_dir](crate::config::artifacts_dir), where `n` counts failures
+/// of the current test starting from 1. Returns paths of the written files
+pub fn write(files: &[(&str, &str)]) -> Result<Vec<PathBuf>> {
+    let n = WRITES.with(|writes| {
+        writes.set(writes.get() + 1);
+        writes.get()
+    });
+    let mut dir = crate::config::artifacts_dir();
+    dir.push(test_name());
+    dir.push(n.to_string());
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create artifacts directory: `{}`", dir.display()))?;
+
+    files
+        .iter()
+        .map(|(name, content)| {
+            let path = dir.join(name);
+            std::fs::write(&path, content)
+                .with_context(|| format!("Failed to write `{}`", path.display()))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Test harness names the thread of every test after it. Assertions running
+/// on other threads get the name of the thread instead
+fn test_name() -> String {
+    let thread = std::thread::current();
+    thread.name().unwrap_or("unnamed").replace("::", "_")
+}
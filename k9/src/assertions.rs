@@ -5,16 +5,31 @@ use colored::*;
 pub mod err_matches_regex;
 #[cfg(feature = "regex")]
 pub mod matches_regex;
+#[cfg(feature = "serde")]
+pub mod roundtrip;
 
+pub mod all;
+pub mod allocations;
+pub mod any;
+pub mod channel;
+pub mod debug_equal;
+pub mod deterministic;
+pub mod display_equal;
 pub mod equal;
+pub mod equal_by;
 pub mod err;
 pub mod greater_than;
 pub mod greater_than_or_equal;
+pub mod iter_equal;
+pub mod laws;
 pub mod lesser_than;
 pub mod lesser_than_or_equal;
+pub mod matches_shape;
 pub mod matches_snapshot;
 pub mod ok;
+pub mod ordered;
 pub mod snapshot;
+pub mod str_equal;
 
 #[derive(Debug)]
 pub struct Assertion {
@@ -92,6 +107,7 @@ pub fn make_assertion_impl(
             name: name.to_string(),
             args_str,
         };
+        crate::config::record_nested_failure(|| all::nested_failure_message(&assertion));
         Some(assertion)
     } else {
         None
@@ -186,6 +202,882 @@ macro_rules! assert_equal {
     }};
 }
 
+/// Asserts that the [Debug](std::fmt::Debug) representation of the value is
+/// equal to the expected string. Useful for types that don't implement [PartialEq].
+///
+/// Single line expected strings are compared with `{:?}` output. If the expected
+/// string spans multiple lines, the value is pretty printed with `{:#?}` instead and
+/// the newlines right after the opening and before the closing quote are ignored.
+///
+/// ```
+/// use k9::assert_debug_equal;
+///
+/// #[derive(Debug)]
+/// struct A {
+///     name: &'static str,
+/// }
+///
+/// assert_debug_equal!(A { name: "Kelly" }, r#"A { name: "Kelly" }"#);
+/// assert_debug_equal!(
+///     A { name: "Kelly" },
+///     r#"
+/// A {
+///     name: "Kelly",
+/// }
+/// "#
+/// );
+/// ```
+///
+/// ```should_panic
+/// # use k9::assert_debug_equal;
+/// // prints the difference between the rendered value and the expected string
+/// assert_debug_equal!(Some(1), "Some(2)");
+/// ```
+#[macro_export]
+macro_rules! assert_debug_equal {
+    ($value:expr, $expected:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!(
+            "{}, {}",
+            stringify!($value).red(),
+            stringify!($expected).green(),
+        );
+        $crate::make_assertion!(
+            "assert_debug_equal",
+            args_str,
+            $crate::assertions::debug_equal::assert_debug_equal(&$value, $expected),
+            None,
+        )
+    }};
+    ($value:expr, $expected:expr, $($description:expr),*) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}, {}",
+            stringify!($value).red(),
+            stringify!($expected).green(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_debug_equal",
+            args_str,
+            $crate::assertions::debug_equal::assert_debug_equal(&$value, $expected),
+            Some(&description),
+        )
+    }};
+}
+
+/// Asserts that the [Display](std::fmt::Display) representation of the value
+/// is equal to the expected string.
+///
+/// If the expected string spans multiple lines, the newlines right after the
+/// opening and before the closing quote are ignored.
+///
+/// ```
+/// use k9::assert_display_equal;
+///
+/// assert_display_equal!(std::net::Ipv4Addr::LOCALHOST, "127.0.0.1");
+/// assert_display_equal!(
+///     "A\nB",
+///     "
+/// A
+/// B
+/// "
+/// );
+/// ```
+///
+/// ```should_panic
+/// # use k9::assert_display_equal;
+/// assert_display_equal!(1.5, "1.50");
+/// ```
+#[macro_export]
+macro_rules! assert_display_equal {
+    ($value:expr, $expected:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!(
+            "{}, {}",
+            stringify!($value).red(),
+            stringify!($expected).green(),
+        );
+        $crate::make_assertion!(
+            "assert_display_equal",
+            args_str,
+            $crate::assertions::display_equal::assert_display_equal(&$value, $expected),
+            None,
+        )
+    }};
+    ($value:expr, $expected:expr, $($description:expr),*) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}, {}",
+            stringify!($value).red(),
+            stringify!($expected).green(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_display_equal",
+            args_str,
+            $crate::assertions::display_equal::assert_display_equal(&$value, $expected),
+            Some(&description),
+        )
+    }};
+}
+
+/// Asserts that two iterators yield equal items.
+///
+/// Iterators are walked in lockstep without collecting them, and the assertion
+/// stops at the first pair of different items or when one of the iterators runs out.
+/// The failure message shows the index of the first difference together with
+/// a few preceding items from both sides.
+///
+/// This makes it possible to compare very long sequences or a prefix of
+/// an infinite iterator (as long as the other one is finite).
+///
+/// ```
+/// use k9::assert_iter_equal;
+///
+/// assert_iter_equal!((1..=3).map(|i| i * 2), vec![2, 4, 6]);
+/// assert_iter_equal!("abc".chars(), ['a', 'b', 'c']);
+/// ```
+///
+/// ```should_panic
+/// # use k9::assert_iter_equal;
+/// // fails at index 5 without generating the rest of the infinite sequence
+/// assert_iter_equal!((0..).map(|i| i * i), vec![0, 1, 4, 9, 16, 24]);
+/// ```
+#[macro_export]
+macro_rules! assert_iter_equal {
+    ($left:expr, $right:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!(
+            "{}, {}",
+            stringify!($left).red(),
+            stringify!($right).green(),
+        );
+        $crate::make_assertion!(
+            "assert_iter_equal",
+            args_str,
+            $crate::assertions::iter_equal::assert_iter_equal($left, $right),
+            None,
+        )
+    }};
+    ($left:expr, $right:expr, $($description:expr),*) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}, {}",
+            stringify!($left).red(),
+            stringify!($right).green(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_iter_equal",
+            args_str,
+            $crate::assertions::iter_equal::assert_iter_equal($left, $right),
+            Some(&description),
+        )
+    }};
+}
+
+/// Asserts that two strings are equal, optionally after normalizing both of them.
+///
+/// Supported normalizations (always applied in this order):
+/// - `crlf` replaces `\r\n` line endings with `\n`
+/// - `collapse_whitespace` replaces every run of spaces and tabs with a single space
+/// - `trim` removes leading and trailing whitespace of the whole string
+/// - `trailing_newline` ignores newlines at the end of the string
+/// - `case` compares case insensitively
+///
+/// The failure message shows the line by line difference of the original,
+/// not normalized, strings.
+///
+/// ```
+/// use k9::assert_str_equal;
+///
+/// assert_str_equal!("hello", "hello");
+/// assert_str_equal!("a\r\nb\n", "a\nb", normalize = [crlf, trailing_newline]);
+/// assert_str_equal!("  Hello   World ", "hello world", normalize = [trim, collapse_whitespace, case]);
+/// ```
+///
+/// ```should_panic
+/// # use k9::assert_str_equal;
+/// assert_str_equal!("a\nb\n", "a\nc\n", normalize = [trailing_newline]);
+/// ```
+#[macro_export]
+macro_rules! assert_str_equal {
+    ($left:expr, $right:expr) => {{
+        $crate::assert_str_equal!($left, $right, normalize = [])
+    }};
+    ($left:expr, $right:expr, normalize = [$($normalize:ident),* $(,)?]) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let normalizations: &[$crate::assertions::str_equal::Normalize] =
+            &[$($crate::__k9_normalize!($normalize)),*];
+        let mut args_str = format!("{}, {}", stringify!($left).red(), stringify!($right).green());
+        if !normalizations.is_empty() {
+            args_str.push_str(&format!(", {}", stringify!(normalize = [$($normalize),*]).yellow()));
+        }
+        $crate::make_assertion!(
+            "assert_str_equal",
+            args_str,
+            $crate::assertions::str_equal::assert_str_equal(&$left, &$right, normalizations),
+            None,
+        )
+    }};
+    ($left:expr, $right:expr, normalize = [$($normalize:ident),* $(,)?], $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let normalizations: &[$crate::assertions::str_equal::Normalize] =
+            &[$($crate::__k9_normalize!($normalize)),*];
+        let mut args_str = format!("{}, {}", stringify!($left).red(), stringify!($right).green());
+        if !normalizations.is_empty() {
+            args_str.push_str(&format!(", {}", stringify!(normalize = [$($normalize),*]).yellow()));
+        }
+        args_str.push_str(&format!(", {}", stringify!($( $description ),* ).dimmed()));
+        $crate::make_assertion!(
+            "assert_str_equal",
+            args_str,
+            $crate::assertions::str_equal::assert_str_equal(&$left, &$right, normalizations),
+            Some(&description),
+        )
+    }};
+    ($left:expr, $right:expr, $($description:expr),+) => {{
+        $crate::assert_str_equal!($left, $right, normalize = [], $($description),+)
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __k9_normalize {
+    (crlf) => {
+        $crate::assertions::str_equal::Normalize::Crlf
+    };
+    (collapse_whitespace) => {
+        $crate::assertions::str_equal::Normalize::CollapseWhitespace
+    };
+    (trim) => {
+        $crate::assertions::str_equal::Normalize::Trim
+    };
+    (trailing_newline) => {
+        $crate::assertions::str_equal::Normalize::TrailingNewline
+    };
+    (case) => {
+        $crate::assertions::str_equal::Normalize::Case
+    };
+}
+
+/// Asserts that every item of the collection satisfies the predicate.
+/// All failing items are reported together with their indices.
+///
+/// The predicate receives a reference to every item and returns either a `bool`
+/// or the result of another k9 assertion, in which case failure messages of
+/// the nested assertion are included for every failing item.
+/// Any other k9 assertion failing inside of the predicate fails the item too.
+///
+/// ```
+/// use k9::{assert_all, assert_greater_than};
+///
+/// assert_all!(vec![1, 2, 3], |x| *x > 0);
+/// assert_all!(vec![1, 2, 3], |x| assert_greater_than!(*x, 0));
+/// ```
+///
+/// ```should_panic
+/// # use k9::{assert_all, assert_greater_than};
+/// assert_all!(vec![1, -2, 3, -4], |x| assert_greater_than!(*x, 0));
+/// ```
+#[macro_export]
+macro_rules! assert_all {
+    ($collection:expr, $predicate:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!(
+            "{}, {}",
+            stringify!($collection).red(),
+            stringify!($predicate).green(),
+        );
+        $crate::make_assertion!(
+            "assert_all",
+            args_str,
+            $crate::assertions::all::assert_all($collection, $predicate),
+            None,
+        )
+    }};
+    ($collection:expr, $predicate:expr, $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}, {}",
+            stringify!($collection).red(),
+            stringify!($predicate).green(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_all",
+            args_str,
+            $crate::assertions::all::assert_all($collection, $predicate),
+            Some(&description),
+        )
+    }};
+}
+
+/// Asserts that at least one item of the collection satisfies the predicate.
+/// If none of them do, a sample of inspected items is reported.
+///
+/// The predicate receives a reference to every item and returns either a `bool`
+/// or the result of another k9 assertion, same as in [assert_all!](crate::assert_all).
+///
+/// ```
+/// use k9::{assert_any, assert_equal};
+///
+/// assert_any!(vec![1, 2, 3], |x| *x == 2);
+/// assert_any!(vec!["a", "b"], |x| assert_equal!(*x, "b"));
+/// ```
+///
+/// ```should_panic
+/// # use k9::assert_any;
+/// assert_any!(vec![1, 2, 3], |x| *x > 5);
+/// ```
+#[macro_export]
+macro_rules! assert_any {
+    ($collection:expr, $predicate:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!(
+            "{}, {}",
+            stringify!($collection).red(),
+            stringify!($predicate).green(),
+        );
+        $crate::make_assertion!(
+            "assert_any",
+            args_str,
+            $crate::assertions::any::assert_any($collection, $predicate),
+            None,
+        )
+    }};
+    ($collection:expr, $predicate:expr, $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}, {}",
+            stringify!($collection).red(),
+            stringify!($predicate).green(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_any",
+            args_str,
+            $crate::assertions::any::assert_any($collection, $predicate),
+            Some(&description),
+        )
+    }};
+}
+
+/// Asserts that the closure allocates at most `n` times on the current thread.
+/// Requires [CountingAllocator](crate::alloc::CountingAllocator) to be installed
+/// as the global allocator, see [alloc](crate::alloc).
+///
+/// ```
+/// use k9::assert_max_allocations;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: k9::alloc::CountingAllocator = k9::alloc::CountingAllocator::system();
+///
+/// assert_max_allocations!(1, || vec![1, 2, 3]);
+/// ```
+///
+/// ```should_panic
+/// # use k9::assert_max_allocations;
+/// # #[global_allocator]
+/// # static ALLOCATOR: k9::alloc::CountingAllocator = k9::alloc::CountingAllocator::system();
+/// assert_max_allocations!(1, || vec![vec![1], vec![2]]);
+/// ```
+#[macro_export]
+macro_rules! assert_max_allocations {
+    ($max:expr, $f:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!("{}, {}", stringify!($max).green(), stringify!($f).red());
+        $crate::make_assertion!(
+            "assert_max_allocations",
+            args_str,
+            $crate::assertions::allocations::assert_max_allocations($max, $f),
+            None,
+        )
+    }};
+    ($max:expr, $f:expr, $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}",
+            format!("{}, {}", stringify!($max).green(), stringify!($f).red()),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_max_allocations",
+            args_str,
+            $crate::assertions::allocations::assert_max_allocations($max, $f),
+            Some(&description),
+        )
+    }};
+}
+
+/// Asserts that the closure doesn't allocate on the current thread.
+/// Requires [CountingAllocator](crate::alloc::CountingAllocator) to be installed
+/// as the global allocator, see [alloc](crate::alloc).
+///
+/// ```
+/// use k9::assert_no_alloc;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: k9::alloc::CountingAllocator = k9::alloc::CountingAllocator::system();
+///
+/// let numbers = [1, 2, 3];
+/// assert_no_alloc!(|| numbers.iter().sum::<i32>());
+/// ```
+///
+/// ```should_panic
+/// # use k9::assert_no_alloc;
+/// # #[global_allocator]
+/// # static ALLOCATOR: k9::alloc::CountingAllocator = k9::alloc::CountingAllocator::system();
+/// assert_no_alloc!(|| format!("{}", 1));
+/// ```
+#[macro_export]
+macro_rules! assert_no_alloc {
+    ($f:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!("{}", stringify!($f).red());
+        $crate::make_assertion!(
+            "assert_no_alloc",
+            args_str,
+            $crate::assertions::allocations::assert_no_alloc($f),
+            None,
+        )
+    }};
+    ($f:expr, $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}",
+            format!("{}", stringify!($f).red()),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_no_alloc",
+            args_str,
+            $crate::assertions::allocations::assert_no_alloc($f),
+            Some(&description),
+        )
+    }};
+}
+
+/// Runs the closure `n` times and asserts that all runs return equal results.
+/// On failure, shows the difference between the first run and the first run
+/// that returned something else.
+///
+/// Pass `threaded` to run every call on its own thread, all at the same time.
+/// Useful for catching bugs that depend on `HashMap` iteration order or thread timing.
+///
+/// ```
+/// use k9::assert_deterministic;
+/// use std::collections::BTreeMap;
+///
+/// assert_deterministic!(10, || (1..5).map(|i| (i, i * 2)).collect::<BTreeMap<_, _>>());
+/// assert_deterministic!(10, threaded, || (1..5).sum::<u32>());
+/// ```
+///
+/// ```should_panic
+/// # use k9::assert_deterministic;
+/// use std::collections::HashMap;
+///
+/// assert_deterministic!(10, || {
+///     let map = (1..20).map(|i| (i, i)).collect::<HashMap<_, _>>();
+///     map.into_iter().collect::<Vec<_>>()
+/// });
+/// ```
+#[macro_export]
+macro_rules! assert_deterministic {
+    ($runs:expr, threaded, $f:expr) => {{
+        $crate::assert_deterministic!(@internal assert_deterministic_threaded, $runs, $f, threaded, None)
+    }};
+    ($runs:expr, threaded, $f:expr, $($description:expr),+) => {{
+        let description = format!($( $description ),*);
+        $crate::assert_deterministic!(
+            @internal assert_deterministic_threaded, $runs, $f, threaded, Some(&description), $($description),+
+        )
+    }};
+    ($runs:expr, $f:expr) => {{
+        $crate::assert_deterministic!(@internal assert_deterministic, $runs, $f, , None)
+    }};
+    ($runs:expr, $f:expr, $($description:expr),+) => {{
+        let description = format!($( $description ),*);
+        $crate::assert_deterministic!(
+            @internal assert_deterministic, $runs, $f, , Some(&description), $($description),+
+        )
+    }};
+    (@internal $fn:ident, $runs:expr, $f:expr, $($threaded:ident)?, $description:expr $(, $description_args:expr)*) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let mut args = vec![stringify!($runs).green().to_string()];
+        $(args.push(stringify!($threaded).to_string());)?
+        args.push(stringify!($f).yellow().to_string());
+        $(args.push(stringify!($description_args).dimmed().to_string());)*
+        let args_str = args.join(", ");
+        $crate::make_assertion!(
+            "assert_deterministic",
+            args_str,
+            $crate::assertions::deterministic::$fn($runs, $f),
+            $description,
+        )
+    }};
+}
+
+/// Asserts that `PartialEq` implementation is an equivalence relation for all of
+/// the given samples: reflexive (`a == a`), symmetric (`a == b` implies `b == a`)
+/// and transitive (`a == b` and `b == c` imply `a == c`).
+///
+/// On failure, prints the smallest set of samples that violate one of the laws.
+///
+/// ```
+/// use k9::assert_eq_laws;
+///
+/// assert_eq_laws!(vec!["a", "b", "a"]);
+/// ```
+///
+/// ```should_panic
+/// # use k9::assert_eq_laws;
+/// assert_eq_laws!(vec![1.0, f64::NAN]);
+/// ```
+#[macro_export]
+macro_rules! assert_eq_laws {
+    ($samples:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!("{}", stringify!($samples).green());
+        $crate::make_assertion!(
+            "assert_eq_laws",
+            args_str,
+            $crate::assertions::laws::assert_eq_laws($samples),
+            None,
+        )
+    }};
+    ($samples:expr, $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}",
+            stringify!($samples).green(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_eq_laws",
+            args_str,
+            $crate::assertions::laws::assert_eq_laws($samples),
+            Some(&description),
+        )
+    }};
+}
+
+/// Asserts that `Ord` implementation is a total order for all of the given samples.
+/// Checks the `PartialEq` laws (see [assert_eq_laws!](crate::assert_eq_laws)),
+/// that `a.cmp(a)` is `Equal`, that `a.cmp(b)` is the reverse of `b.cmp(a)`,
+/// that `cmp` is transitive and consistent with `==` and `partial_cmp`.
+///
+/// On failure, prints the smallest set of samples that violate one of the laws.
+///
+/// ```
+/// use k9::assert_ord_laws;
+///
+/// assert_ord_laws!(vec![(1, "b"), (1, "a"), (0, "c")]);
+/// ```
+#[macro_export]
+macro_rules! assert_ord_laws {
+    ($samples:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!("{}", stringify!($samples).green());
+        $crate::make_assertion!(
+            "assert_ord_laws",
+            args_str,
+            $crate::assertions::laws::assert_ord_laws($samples),
+            None,
+        )
+    }};
+    ($samples:expr, $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}",
+            stringify!($samples).green(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_ord_laws",
+            args_str,
+            $crate::assertions::laws::assert_ord_laws($samples),
+            Some(&description),
+        )
+    }};
+}
+
+/// Asserts that `Hash` implementation is consistent with `Eq` for all of the given
+/// samples: `a == b` implies `hash(a) == hash(b)`.
+///
+/// ```
+/// use k9::assert_hash_consistent;
+///
+/// assert_hash_consistent!(vec!["a".to_string(), "b".to_string(), "a".to_string()]);
+/// ```
+#[macro_export]
+macro_rules! assert_hash_consistent {
+    ($samples:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!("{}", stringify!($samples).green());
+        $crate::make_assertion!(
+            "assert_hash_consistent",
+            args_str,
+            $crate::assertions::laws::assert_hash_consistent($samples),
+            None,
+        )
+    }};
+    ($samples:expr, $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}",
+            stringify!($samples).green(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_hash_consistent",
+            args_str,
+            $crate::assertions::laws::assert_hash_consistent($samples),
+            Some(&description),
+        )
+    }};
+}
+
+/// Asserts that the channel receives a message equal to `expected` within the timeout.
+/// Works with `std::sync::mpsc::Receiver` and, with `crossbeam` feature enabled,
+/// with `crossbeam_channel::Receiver`.
+///
+/// On failure, messages that are still queued in the channel are printed too (up to 20).
+///
+/// ```
+/// use k9::assert_receives;
+/// use std::time::Duration;
+///
+/// let (tx, rx) = std::sync::mpsc::channel();
+/// std::thread::spawn(move || tx.send("pong").unwrap());
+///
+/// assert_receives!(rx, "pong", Duration::from_secs(1));
+/// ```
+#[macro_export]
+macro_rules! assert_receives {
+    ($receiver:expr, $expected:expr, $timeout:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!(
+            "{}, {}, {}",
+            stringify!($receiver).yellow(),
+            stringify!($expected).green(),
+            stringify!($timeout).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_receives",
+            args_str,
+            $crate::assertions::channel::assert_receives(&$receiver, $expected, $timeout),
+            None,
+        )
+    }};
+    ($receiver:expr, $expected:expr, $timeout:expr, $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}, {}, {}",
+            stringify!($receiver).yellow(),
+            stringify!($expected).green(),
+            stringify!($timeout).dimmed(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_receives",
+            args_str,
+            $crate::assertions::channel::assert_receives(&$receiver, $expected, $timeout),
+            Some(&description),
+        )
+    }};
+}
+
+/// Asserts that the channel doesn't receive any messages within the timeout.
+/// A disconnected channel counts as not receiving anything.
+///
+/// ```
+/// use k9::assert_no_message;
+/// use std::time::Duration;
+///
+/// let (_tx, rx) = std::sync::mpsc::channel::<u32>();
+///
+/// assert_no_message!(rx, Duration::from_millis(10));
+/// ```
+#[macro_export]
+macro_rules! assert_no_message {
+    ($receiver:expr, $timeout:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!(
+            "{}, {}",
+            stringify!($receiver).yellow(),
+            stringify!($timeout).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_no_message",
+            args_str,
+            $crate::assertions::channel::assert_no_message(&$receiver, $timeout),
+            None,
+        )
+    }};
+    ($receiver:expr, $timeout:expr, $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}, {}",
+            stringify!($receiver).yellow(),
+            stringify!($timeout).dimmed(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_no_message",
+            args_str,
+            $crate::assertions::channel::assert_no_message(&$receiver, $timeout),
+            Some(&description),
+        )
+    }};
+}
+
+/// Same as [assert_equal!](crate::assert_equal), but the values are compared using
+/// the given closure instead of `PartialEq`. The failure message still shows the diff
+/// of the full values.
+///
+/// ```
+/// use k9::assert_equal_by;
+///
+/// assert_equal_by!(1.0_f64, 1.0000001, |a, b| (a - b).abs() < 1e-3);
+/// assert_equal_by!("Hello", "hello", |a, b| a.eq_ignore_ascii_case(b));
+/// ```
+#[macro_export]
+macro_rules! assert_equal_by {
+    ($left:expr, $right:expr, $comparator:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!(
+            "{}, {}, {}",
+            stringify!($left).red(),
+            stringify!($right).green(),
+            stringify!($comparator).yellow(),
+        );
+        $crate::make_assertion!(
+            "assert_equal_by",
+            args_str,
+            $crate::assertions::equal_by::assert_equal_by(&$left, &$right, $comparator),
+            None,
+        )
+    }};
+    ($left:expr, $right:expr, $comparator:expr, $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}, {}, {}",
+            stringify!($left).red(),
+            stringify!($right).green(),
+            stringify!($comparator).yellow(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_equal_by",
+            args_str,
+            $crate::assertions::equal_by::assert_equal_by(&$left, &$right, $comparator),
+            Some(&description),
+        )
+    }};
+}
+
+/// Same as [assert_equal!](crate::assert_equal), but the values are compared by the
+/// key that the closure projects them to. The failure message shows both keys side
+/// by side, followed by the diff of the full values.
+///
+/// ```
+/// use k9::assert_equal_by_key;
+///
+/// #[derive(Debug)]
+/// struct User {
+///     id: u32,
+///     name: &'static str,
+/// }
+///
+/// assert_equal_by_key!(
+///     User { id: 1, name: "Kelly" },
+///     User { id: 1, name: "Kelly K." },
+///     |user| user.id
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_equal_by_key {
+    ($left:expr, $right:expr, $key:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!(
+            "{}, {}, {}",
+            stringify!($left).red(),
+            stringify!($right).green(),
+            stringify!($key).yellow(),
+        );
+        $crate::make_assertion!(
+            "assert_equal_by_key",
+            args_str,
+            $crate::assertions::equal_by::assert_equal_by_key(&$left, &$right, $key),
+            None,
+        )
+    }};
+    ($left:expr, $right:expr, $key:expr, $($description:expr),+) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description = format!($( $description ),*);
+        let args_str = format!(
+            "{}, {}, {}, {}",
+            stringify!($left).red(),
+            stringify!($right).green(),
+            stringify!($key).yellow(),
+            stringify!($( $description ),* ).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_equal_by_key",
+            args_str,
+            $crate::assertions::equal_by::assert_equal_by_key(&$left, &$right, $key),
+            Some(&description),
+        )
+    }};
+}
+
 /// Asserts if left is greater than right.
 /// panics if they are not
 ///
@@ -360,6 +1252,147 @@ macro_rules! assert_lesser_than_or_equal {
     }};
 }
 
+/// Asserts that a chain of comparisons holds, e.g. `a < b <= c`.
+/// Every operand is evaluated exactly once. Unlike the separate ordering macros,
+/// operators can be mixed and neighbouring operands can be of different types,
+/// as long as the left one implements `PartialOrd` for the right one.
+///
+/// On failure, the message shows which comparisons didn't hold along with
+/// every operand's expression and value.
+///
+/// Generic arguments after `::` (e.g. `"5".parse::<u32>()`) and qualified paths
+/// at the start of an operand (e.g. `<T as Trait>::f()`) can be used as is. Other
+/// operands that contain `<` or `>` themselves (e.g. `x as Wrapper<T>`) need to be
+/// wrapped in parentheses.
+///
+/// ```rust
+/// use k9::assert_ordered;
+///
+/// let (low, value, high) = (1, 5, 10);
+/// assert_ordered!(low < value <= high);
+/// assert_ordered!(10 >= value > low, "value must be in range");
+/// ```
+#[macro_export]
+macro_rules! assert_ordered {
+    ($($tokens:tt)+) => {
+        $crate::__k9_ordered!(@split [] $($tokens)+)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __k9_ordered {
+    // Separate the chain from the description at the top level `,`
+    (@split [$($chain:tt)+] , $($description:tt)+) => {
+        $crate::__k9_ordered!(@assert [$($chain)+] [$($description)+])
+    };
+    (@split [$($chain:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__k9_ordered!(@split [$($chain)* $next] $($rest)*)
+    };
+    (@split [$($chain:tt)+]) => {
+        $crate::__k9_ordered!(@assert [$($chain)+] [])
+    };
+    (@assert [$($chain:tt)+] [$($description:tt)*]) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let description: Option<String> = $crate::__k9_optional_format!($($description)*);
+        let args_str = match stringify!($($description)*) {
+            "" => stringify!($($chain)+).yellow().to_string(),
+            description => format!(
+                "{}, {}",
+                stringify!($($chain)+).yellow(),
+                description.dimmed()
+            ),
+        };
+        let mut operands: Vec<(&str, &dyn std::fmt::Debug)> = Vec::new();
+        let mut links = Vec::new();
+        $crate::__k9_ordered!(@collect operands links [] [] $($chain)+);
+        $crate::make_assertion!(
+            "assert_ordered",
+            args_str,
+            $crate::assertions::ordered::assert_ordered(&operands, &links),
+            description.as_deref(),
+        )
+    }};
+    // Generic arguments of a turbofish (`parse::<u32>()`) or a qualified path at the
+    // start of an operand (`<T as Trait>::f()`) are taken as a whole, since their
+    // `<` and `>` aren't comparisons
+    (@collect $operands:ident $links:ident $prev:tt [$($operand:tt)*] :: < $($rest:tt)+) => {
+        $crate::__k9_ordered!(@generic $operands $links $prev [$($operand)* ::<] [()] $($rest)+)
+    };
+    (@collect $operands:ident $links:ident $prev:tt [] < $($rest:tt)+) => {
+        $crate::__k9_ordered!(@generic $operands $links $prev [<] [()] $($rest)+)
+    };
+    (@collect $operands:ident $links:ident $prev:tt [] << $($rest:tt)+) => {
+        $crate::__k9_ordered!(@generic $operands $links $prev [<<] [() ()] $($rest)+)
+    };
+    // Accumulate operand tokens until the next comparison operator
+    (@collect $operands:ident $links:ident $prev:tt [$($operand:tt)+] < $($rest:tt)+) => {
+        $crate::__k9_ordered!(@push $operands $links $prev [$($operand)+] Less $($rest)+)
+    };
+    (@collect $operands:ident $links:ident $prev:tt [$($operand:tt)+] <= $($rest:tt)+) => {
+        $crate::__k9_ordered!(@push $operands $links $prev [$($operand)+] LessOrEqual $($rest)+)
+    };
+    (@collect $operands:ident $links:ident $prev:tt [$($operand:tt)+] > $($rest:tt)+) => {
+        $crate::__k9_ordered!(@push $operands $links $prev [$($operand)+] Greater $($rest)+)
+    };
+    (@collect $operands:ident $links:ident $prev:tt [$($operand:tt)+] >= $($rest:tt)+) => {
+        $crate::__k9_ordered!(@push $operands $links $prev [$($operand)+] GreaterOrEqual $($rest)+)
+    };
+    (@collect $operands:ident $links:ident $prev:tt [$($operand:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__k9_ordered!(@collect $operands $links $prev [$($operand)* $next] $($rest)*)
+    };
+    (@collect $operands:ident $links:ident [] [$($operand:tt)+]) => {
+        compile_error!("assert_ordered! expects at least one comparison, e.g. `a < b`")
+    };
+    (@collect $operands:ident $links:ident [$prev:ident $comparison:ident] [$($operand:tt)+]) => {
+        $crate::__k9_ordered!(@evaluate $operands $links [$prev $comparison] [$($operand)+] operand);
+    };
+    // `[$($depth:tt)+]` has a `()` for every `<` that isn't closed yet. `>>` is a single
+    // token, so it closes two of them
+    (@generic $operands:ident $links:ident $prev:tt [$($operand:tt)+] [()] > $($rest:tt)*) => {
+        $crate::__k9_ordered!(@collect $operands $links $prev [$($operand)+ >] $($rest)*)
+    };
+    (@generic $operands:ident $links:ident $prev:tt [$($operand:tt)+] [() $($depth:tt)+] > $($rest:tt)*) => {
+        $crate::__k9_ordered!(@generic $operands $links $prev [$($operand)+ >] [$($depth)+] $($rest)*)
+    };
+    (@generic $operands:ident $links:ident $prev:tt [$($operand:tt)+] [()] >> $($rest:tt)*) => {
+        $crate::__k9_ordered!(@collect $operands $links $prev [$($operand)+ >] > $($rest)*)
+    };
+    (@generic $operands:ident $links:ident $prev:tt [$($operand:tt)+] [() ()] >> $($rest:tt)*) => {
+        $crate::__k9_ordered!(@collect $operands $links $prev [$($operand)+ >>] $($rest)*)
+    };
+    (@generic $operands:ident $links:ident $prev:tt [$($operand:tt)+] [() () $($depth:tt)+] >> $($rest:tt)*) => {
+        $crate::__k9_ordered!(@generic $operands $links $prev [$($operand)+ >>] [$($depth)+] $($rest)*)
+    };
+    (@generic $operands:ident $links:ident $prev:tt [$($operand:tt)+] [$($depth:tt)+] < $($rest:tt)*) => {
+        $crate::__k9_ordered!(@generic $operands $links $prev [$($operand)+ <] [() $($depth)+] $($rest)*)
+    };
+    (@generic $operands:ident $links:ident $prev:tt [$($operand:tt)+] [$($depth:tt)+] << $($rest:tt)*) => {
+        $crate::__k9_ordered!(@generic $operands $links $prev [$($operand)+ <<] [() () $($depth)+] $($rest)*)
+    };
+    (@generic $operands:ident $links:ident $prev:tt [$($operand:tt)+] [$($depth:tt)+] $next:tt $($rest:tt)*) => {
+        $crate::__k9_ordered!(@generic $operands $links $prev [$($operand)+ $next] [$($depth)+] $($rest)*)
+    };
+    // `operand` is created by this expansion, so it doesn't clash with the other
+    // operands and can be referred to by the next link of the chain
+    (@push $operands:ident $links:ident $prev:tt [$($operand:tt)+] $comparison:ident $($rest:tt)+) => {
+        $crate::__k9_ordered!(@evaluate $operands $links $prev [$($operand)+] operand);
+        $crate::__k9_ordered!(@collect $operands $links [operand $comparison] [] $($rest)+);
+    };
+    (@evaluate $operands:ident $links:ident [$($prev:ident $comparison:ident)?] [$($operand:tt)+] $name:ident) => {
+        let $name = &($($operand)+);
+        $operands.push((stringify!($($operand)+), $name as &dyn std::fmt::Debug));
+        $(
+            $links.push($crate::assertions::ordered::compare(
+                $prev,
+                $name,
+                $crate::assertions::ordered::Comparison::$comparison,
+            ));
+        )?
+    };
+}
+
 /// Asserts that passed `&str` matches a regular expression.
 /// Regular expressions are compiled using `regex` crate.
 ///
@@ -706,3 +1739,464 @@ macro_rules! snapshot {
         )
     }};
 }
+
+/// Same as [snapshot!](crate::snapshot), but snapshots everything written to
+/// `stdout` and `stderr` by the closure. See [capture_output](crate::capture_output).
+///
+/// ```rust
+/// k9::snapshot_output!(
+///     || {
+///         println!("Hello");
+///         eprintln!("warning: world");
+///     },
+///     "
+/// stdout:
+/// Hello
+/// stderr:
+/// warning: world
+///
+/// "
+/// );
+/// ```
+#[cfg(unix)]
+#[macro_export]
+macro_rules! snapshot_output {
+    ($f:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let line = line!();
+        let column = column!();
+        let file = file!();
+        let args_str = format!("{}", stringify!($f).red(),);
+        $crate::make_assertion!(
+            "snapshot_output",
+            args_str,
+            $crate::assertions::snapshot::snapshot_output(
+                $crate::capture_output($f),
+                None,
+                line,
+                column,
+                file,
+            ),
+            None,
+        )
+    }};
+    ($f:expr, $inline_snap:literal) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let line = line!();
+        let column = column!();
+        let file = file!();
+        let args_str = format!(
+            "{}, {}",
+            stringify!($f).red(),
+            stringify!($inline_snap).green(),
+        );
+        $crate::make_assertion!(
+            "snapshot_output",
+            args_str,
+            $crate::assertions::snapshot::snapshot_output(
+                $crate::capture_output($f),
+                Some($inline_snap),
+                line,
+                column,
+                file,
+            ),
+            None,
+        )
+    }};
+}
+
+/// Asserts that the value matches a partial template.
+/// Only fields listed in the template are compared, everything else
+/// (including extra map keys) is ignored.
+///
+/// Template syntax:
+/// - `{ field: template, ... }` for struct fields
+/// - `{ 0: template }` for tuple and tuple struct items
+/// - `{ "key" => template }` for map entries
+/// - `[template, template]` for sequences. Number of items must match
+/// - any other expression is compared with the value by its `{:#?}` representation
+/// - matchers from [k9::shape](crate::shape) (e.g. `any_string()`) can be used in place of values
+///
+/// ```rust
+/// use k9::assert_matches_shape;
+/// use k9::shape::any_string;
+///
+/// #[derive(Debug)]
+/// struct User {
+///     id: u64,
+///     name: &'static str,
+///     tags: Vec<&'static str>,
+/// }
+///
+/// let user = User { id: 123, name: "Kelly", tags: vec!["admin"] };
+///
+/// assert_matches_shape!(user, {
+///     name: any_string(),
+///     tags: ["admin"],
+/// });
+/// ```
+///
+/// ```should_panic
+/// # use k9::assert_matches_shape;
+/// # #[derive(Debug)]
+/// # struct User {
+/// #     id: u64,
+/// #     name: &'static str,
+/// # }
+/// let user = User { id: 123, name: "Kelly" };
+///
+/// // prints the difference for `id` and `name` fields only
+/// assert_matches_shape!(user, { id: 1, name: "Kelly" });
+/// ```
+#[macro_export]
+macro_rules! assert_matches_shape {
+    ($value:expr, $shape:tt) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!(
+            "{}, {}",
+            stringify!($value).red(),
+            stringify!($shape).green(),
+        );
+        $crate::make_assertion!(
+            "assert_matches_shape",
+            args_str,
+            $crate::assertions::matches_shape::assert_matches_shape(
+                &$value,
+                $crate::__k9_shape!($shape),
+            ),
+            None,
+        )
+    }};
+    ($value:expr, $shape:tt, $description:expr) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let args_str = format!(
+            "{}, {}, {}",
+            stringify!($value).red(),
+            stringify!($shape).green(),
+            stringify!($description).dimmed(),
+        );
+        $crate::make_assertion!(
+            "assert_matches_shape",
+            args_str,
+            $crate::assertions::matches_shape::assert_matches_shape(
+                &$value,
+                $crate::__k9_shape!($shape),
+            ),
+            Some(&$description),
+        )
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __k9_shape {
+    ({ $($fields:tt)* }) => {
+        $crate::assertions::matches_shape::Shape::Fields($crate::__k9_shape_fields!([] $($fields)*))
+    };
+    ([ $($items:tt)* ]) => {
+        $crate::assertions::matches_shape::Shape::Items($crate::__k9_shape_items!([] $($items)*))
+    };
+    ($leaf:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::assertions::matches_shape::{MatcherLeaf, ValueLeaf};
+        (&&$crate::assertions::matches_shape::ShapeLeaf(&$leaf)).to_shape()
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __k9_shape_fields {
+    ([$($out:expr,)*]) => {
+        vec![$($out,)*]
+    };
+    ([$($out:expr,)*] $field:ident : $($rest:tt)*) => {
+        $crate::__k9_shape_entry!(
+            [$($out,)*]
+            [$crate::assertions::matches_shape::ShapeKey::Field(stringify!($field).to_string())]
+            $($rest)*
+        )
+    };
+    ([$($out:expr,)*] $index:literal : $($rest:tt)*) => {
+        $crate::__k9_shape_entry!(
+            [$($out,)*]
+            [$crate::assertions::matches_shape::ShapeKey::Field(stringify!($index).to_string())]
+            $($rest)*
+        )
+    };
+    ([$($out:expr,)*] $key:literal => $($rest:tt)*) => {
+        $crate::__k9_shape_entry!(
+            [$($out,)*]
+            [$crate::assertions::matches_shape::ShapeKey::Key(format!("{:#?}", $key))]
+            $($rest)*
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __k9_shape_entry {
+    ([$($out:expr,)*] [$key:expr] { $($shape:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::__k9_shape_fields!([$($out,)* ($key, $crate::__k9_shape!({ $($shape)* })),] $($($rest)*)?)
+    };
+    ([$($out:expr,)*] [$key:expr] [ $($shape:tt)* ] $(, $($rest:tt)*)?) => {
+        $crate::__k9_shape_fields!([$($out,)* ($key, $crate::__k9_shape!([ $($shape)* ])),] $($($rest)*)?)
+    };
+    ([$($out:expr,)*] [$key:expr] $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__k9_shape_fields!([$($out,)* ($key, $crate::__k9_shape!($value)),] $($($rest)*)?)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __k9_shape_items {
+    ([$($out:expr,)*]) => {
+        vec![$($out,)*]
+    };
+    ([$($out:expr,)*] { $($shape:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::__k9_shape_items!([$($out,)* $crate::__k9_shape!({ $($shape)* }),] $($($rest)*)?)
+    };
+    ([$($out:expr,)*] [ $($shape:tt)* ] $(, $($rest:tt)*)?) => {
+        $crate::__k9_shape_items!([$($out,)* $crate::__k9_shape!([ $($shape)* ]),] $($($rest)*)?)
+    };
+    ([$($out:expr,)*] $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__k9_shape_items!([$($out,)* $crate::__k9_shape!($value),] $($($rest)*)?)
+    };
+}
+
+/// Asserts at compile time that the type implements all of the listed traits.
+/// Fails compilation with an error naming the missing trait if it does not.
+///
+/// Can be used both at the module level and inside of functions.
+///
+/// ```rust
+/// use k9::assert_impl;
+///
+/// struct Config {
+///     name: String,
+/// }
+///
+/// assert_impl!(Config: Send + Sync);
+/// assert_impl!(Vec<u8>: Send + Sync + Clone + std::fmt::Debug);
+/// ```
+///
+/// ```compile_fail
+/// use k9::assert_impl;
+///
+/// struct Handle(std::rc::Rc<u8>);
+///
+/// // error[E0277]: `Rc<u8>` cannot be sent between threads safely
+/// assert_impl!(Handle: Send);
+/// ```
+#[macro_export]
+macro_rules! assert_impl {
+    ($type:ty: $($bounds:tt)+) => {
+        const _: fn() = || {
+            fn assert_impl<T: ?Sized + $($bounds)+>() {}
+            assert_impl::<$type>();
+        };
+    };
+}
+
+/// Asserts at compile time that the type does not implement the listed traits.
+/// If multiple traits are listed, compilation fails only when the type implements
+/// all of them.
+///
+/// ```rust
+/// use k9::assert_not_impl;
+///
+/// struct Handle(std::rc::Rc<u8>);
+///
+/// assert_not_impl!(Handle: Send);
+/// assert_not_impl!(Handle: Clone);
+/// ```
+///
+/// ```compile_fail
+/// use k9::assert_not_impl;
+///
+/// // error[E0080]: evaluation panicked: `u8` must not implement `Send`
+/// assert_not_impl!(u8: Send);
+/// ```
+#[macro_export]
+macro_rules! assert_not_impl {
+    ($type:ty: $($bounds:tt)+) => {
+        const _: () = {
+            struct Check<T: ?Sized>(::core::marker::PhantomData<T>);
+
+            trait NotImplemented {
+                const IMPLEMENTED: bool = false;
+            }
+            impl<T: ?Sized> NotImplemented for Check<T> {}
+
+            // Inherent associated consts take precedence over trait ones, but only
+            // exist if the bounds are satisfied.
+            #[allow(dead_code)]
+            impl<T: ?Sized + $($bounds)+> Check<T> {
+                const IMPLEMENTED: bool = true;
+            }
+
+            assert!(
+                !Check::<$type>::IMPLEMENTED,
+                concat!(
+                    "`",
+                    stringify!($type),
+                    "` must not implement `",
+                    stringify!($($bounds)+),
+                    "`"
+                )
+            );
+        };
+    };
+}
+
+/// Asserts at compile time that the size of the type is exactly the given
+/// number of bytes. The compile error shows both the actual and expected sizes.
+///
+/// ```rust
+/// use k9::assert_size;
+///
+/// assert_size!(u64, 8);
+/// assert_size!(Option<Box<u8>>, std::mem::size_of::<usize>());
+/// ```
+///
+/// ```compile_fail
+/// use k9::assert_size;
+///
+/// // error[E0512]: cannot transmute between types of different sizes
+/// //    = note: source type: `(u64, u8)` (128 bits)
+/// //    = note: target type: `[u8; 9]` (72 bits)
+/// assert_size!((u64, u8), 9);
+/// ```
+#[macro_export]
+macro_rules! assert_size {
+    ($type:ty, $size:expr) => {
+        const _: fn() = || {
+            let _ = ::core::mem::transmute::<$type, [u8; $size]>;
+        };
+    };
+}
+
+/// Serializes the value, deserializes it back and asserts that the result is equal
+/// to the original value, same as [assert_equal!](crate::assert_equal).
+/// Requires `serde` feature.
+///
+/// Supported formats:
+/// - `format = json` (default) using `serde_json`, requires `serde_json` feature
+/// - `format = bincode` using `bincode`, requires `bincode` feature
+/// - `format = custom(serialize, deserialize)` with any pair of closures returning `Result`s
+///
+/// `serde_json` implements `PartialEq` between its `Value` and primitive types, so
+/// linking it can make expressions like `assert_equal!(1, s.parse().unwrap())` ambiguous
+/// (use `s.parse::<i32>()` instead). That's why json and bincode formats are opt-in.
+///
+/// The serialized form can also be compared to an inline snapshot by passing
+/// `snapshot = ""` as the last argument and running tests with `K9_UPDATE_SNAPSHOTS=1`.
+/// JSON is pretty printed, binary formats are stored as a hex dump.
+///
+/// ```
+/// # #[cfg(all(feature = "serde_json", feature = "bincode"))]
+/// # {
+/// use k9::assert_roundtrip;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Message {
+///     id: u32,
+///     body: String,
+/// }
+///
+/// let message = Message { id: 1, body: "hello".to_string() };
+///
+/// assert_roundtrip!(message);
+/// assert_roundtrip!(message, format = bincode);
+/// assert_roundtrip!(
+///     message,
+///     format = custom(serde_json::to_vec, |bytes: &Vec<u8>| serde_json::from_slice(bytes))
+/// );
+/// assert_roundtrip!(
+///     message,
+///     format = json,
+///     snapshot = r#"
+/// {
+///   "id": 1,
+///   "body": "hello"
+/// }
+/// "#
+/// );
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+#[macro_export]
+macro_rules! assert_roundtrip {
+    ($value:expr $(,)?) => {{
+        $crate::assert_roundtrip!(@impl $value, [json], [], [])
+    }};
+    ($value:expr, format = $format:ident $(($($args:expr),* $(,)?))? $(, snapshot = $snapshot:literal)? $(,)?) => {{
+        $crate::assert_roundtrip!(@impl $value, [$format $(($($args),*))?], [$($snapshot)?], [])
+    }};
+    ($value:expr, snapshot = $snapshot:literal $(,)?) => {{
+        $crate::assert_roundtrip!(@impl $value, [json], [$snapshot], [])
+    }};
+    ($value:expr, format = $format:ident $(($($args:expr),* $(,)?))?, $($description:expr),+) => {{
+        $crate::assert_roundtrip!(@impl $value, [$format $(($($args),*))?], [], [$($description),+])
+    }};
+    ($value:expr, $($description:expr),+) => {{
+        $crate::assert_roundtrip!(@impl $value, [json], [], [$($description),+])
+    }};
+    (@impl $value:expr, [$($format:tt)*], [$($snapshot:literal)?], [$($description:expr),*]) => {{
+        use $crate::__macros__::colored::*;
+        $crate::assertions::initialize_colors();
+        let line = line!();
+        let file = file!();
+        let description: Option<String> = $crate::__k9_optional_format!($($description),*);
+        let mut args = vec![
+            stringify!($value).red().to_string(),
+            format!("format = {}", stringify!($($format)*)).yellow().to_string(),
+        ];
+        $(args.push(format!("snapshot = {}", stringify!($snapshot)).green().to_string());)?
+        $(args.push(stringify!($description).dimmed().to_string());)*
+        let args_str = args.join(", ");
+        let snapshot: Option<&str> = None $(.or(Some($snapshot)))?;
+        $crate::make_assertion!(
+            "assert_roundtrip",
+            args_str,
+            $crate::assertions::roundtrip::assert_roundtrip(
+                &$value,
+                $crate::__k9_roundtrip_format!($($format)*),
+                snapshot,
+                line,
+                file,
+            ),
+            description.as_deref(),
+        )
+    }};
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __k9_roundtrip_format {
+    (json) => {
+        $crate::assertions::roundtrip::Json
+    };
+    (bincode) => {
+        $crate::assertions::roundtrip::Bincode
+    };
+    (custom($serialize:expr, $deserialize:expr)) => {
+        $crate::assertions::roundtrip::Custom::new($serialize, $deserialize)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __k9_optional_format {
+    () => {
+        None
+    };
+    ($($description:expr),+) => {
+        Some(format!($($description),+))
+    };
+}
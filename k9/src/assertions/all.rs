@@ -0,0 +1,129 @@
+use super::Assertion;
+use colored::*;
+use std::fmt::Debug;
+
+/// Something that can be returned from an [assert_all!](crate::assert_all) or
+/// [assert_any!](crate::assert_any) predicate. Either a plain `bool` or the
+/// result of another k9 assertion.
+pub trait PredicateResult {
+    /// `Err` if the item didn't satisfy the predicate, with the nested
+    /// assertion failure message if there is one.
+    fn check(self) -> Result<(), Option<String>>;
+}
+
+impl PredicateResult for bool {
+    fn check(self) -> Result<(), Option<String>> {
+        if self {
+            Ok(())
+        } else {
+            Err(None)
+        }
+    }
+}
+
+impl PredicateResult for Option<Assertion> {
+    fn check(self) -> Result<(), Option<String>> {
+        match self {
+            None => Ok(()),
+            Some(assertion) => Err(Some(nested_failure_message(&assertion))),
+        }
+    }
+}
+
+/// Failure message of an assertion nested inside of a predicate
+pub(crate) fn nested_failure_message(assertion: &Assertion) -> String {
+    format!(
+        "{}\n{}{}",
+        assertion.assertion_expression(),
+        assertion
+            .description
+            .as_ref()
+            .map(|d| format!("{}\n", d))
+            .unwrap_or_default(),
+        assertion.failure_message.trim(),
+    )
+}
+
+/// Run the predicate making sure nested assertions don't panic.
+/// Any assertion that fails while the predicate runs fails the item,
+/// even if the predicate doesn't return it.
+pub(crate) fn check_item<T, F, R>(item: &T, predicate: &mut F) -> Result<(), Option<String>>
+where
+    F: FnMut(&T) -> R,
+    R: PredicateResult,
+{
+    let (result, mut failures) = crate::config::nested_assertions(|| predicate(item).check());
+    if let Err(Some(returned)) = &result {
+        // the returned assertion was recorded too, don't report it twice
+        if !failures.contains(returned) {
+            failures.push(returned.clone());
+        }
+    }
+    if failures.is_empty() {
+        result
+    } else {
+        Err(Some(failures.join("\n\n")))
+    }
+}
+
+/// `[index] item` followed by the nested assertion failure, indented under it
+pub(crate) fn format_item<T: Debug>(index: usize, item: &T, failure: Option<&str>) -> String {
+    let mut result = format!("[{}] {}", index, indent(&format!("{:#?}", item)).red());
+    if let Some(failure) = failure {
+        result.push_str("\n    ");
+        result.push_str(&indent(failure));
+        result.push('\n');
+    }
+    result
+}
+
+fn indent(s: &str) -> String {
+    s.lines()
+        .map(|line| {
+            if line.is_empty() {
+                line.to_string()
+            } else {
+                format!("    {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_start()
+        .to_string()
+}
+
+pub fn assert_all<I, F, R>(collection: I, mut predicate: F) -> Option<String>
+where
+    I: IntoIterator,
+    I::Item: Debug,
+    F: FnMut(&I::Item) -> R,
+    R: PredicateResult,
+{
+    let mut total = 0;
+    let mut failures = vec![];
+
+    for (index, item) in collection.into_iter().enumerate() {
+        total += 1;
+        if let Err(failure) = check_item(&item, &mut predicate) {
+            failures.push(format_item(index, &item, failure.as_deref()));
+        }
+    }
+
+    if failures.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "
+Expected all items of `{collection_desc}` to satisfy `{predicate_desc}`,
+but {failed} of {total} items didn't:
+
+{failures}
+",
+        collection_desc = "Collection".red(),
+        predicate_desc = "Predicate".green(),
+        failed = failures.len(),
+        total = total,
+        failures = failures.join("\n").trim_end(),
+    ))
+}
@@ -0,0 +1,70 @@
+use crate::alloc::{count_allocations, is_installed, AllocationStats};
+use colored::*;
+
+pub fn assert_max_allocations<F: FnOnce() -> T, T>(max: usize, f: F) -> Option<String> {
+    if !is_installed() {
+        return Some(not_installed_message());
+    }
+
+    let (result, stats) = count_allocations(f);
+    drop(result);
+
+    if stats.allocations <= max {
+        return None;
+    }
+
+    Some(format!(
+        "Expected {closure_desc} to allocate at most {max} times,
+but it allocated {allocations} times
+
+{stats}
+",
+        closure_desc = "closure".red(),
+        max = max.to_string().green(),
+        allocations = stats.allocations.to_string().red(),
+        stats = format_stats(&stats),
+    ))
+}
+
+pub fn assert_no_alloc<F: FnOnce() -> T, T>(f: F) -> Option<String> {
+    if !is_installed() {
+        return Some(not_installed_message());
+    }
+
+    let (result, stats) = count_allocations(f);
+    drop(result);
+
+    if stats.allocations == 0 {
+        return None;
+    }
+
+    Some(format!(
+        "Expected {closure_desc} not to allocate,
+but it allocated {allocations} times
+
+{stats}
+",
+        closure_desc = "closure".red(),
+        allocations = stats.allocations.to_string().red(),
+        stats = format_stats(&stats),
+    ))
+}
+
+fn format_stats(stats: &AllocationStats) -> String {
+    format!(
+        "Allocations:     {}\nBytes allocated: {}\nPeak memory:     {} bytes",
+        stats.allocations, stats.bytes_allocated, stats.peak_bytes
+    )
+}
+
+fn not_installed_message() -> String {
+    format!(
+        "Allocations can't be counted because {allocator} is not installed.
+Add this to the test binary:
+
+    #[global_allocator]
+    static ALLOCATOR: k9::alloc::CountingAllocator = k9::alloc::CountingAllocator::system();
+",
+        allocator = "k9::alloc::CountingAllocator".yellow(),
+    )
+}
@@ -0,0 +1,53 @@
+use super::all::{check_item, format_item, PredicateResult};
+use colored::*;
+use std::fmt::Debug;
+
+/// Number of inspected items printed when none of them satisfied the predicate
+const SAMPLE_SIZE: usize = 10;
+
+pub fn assert_any<I, F, R>(collection: I, mut predicate: F) -> Option<String>
+where
+    I: IntoIterator,
+    I::Item: Debug,
+    F: FnMut(&I::Item) -> R,
+    R: PredicateResult,
+{
+    let mut total = 0;
+    let mut sample = vec![];
+
+    for (index, item) in collection.into_iter().enumerate() {
+        total += 1;
+        match check_item(&item, &mut predicate) {
+            Ok(()) => return None,
+            Err(failure) => {
+                if sample.len() < SAMPLE_SIZE {
+                    sample.push(format_item(index, &item, failure.as_deref()));
+                }
+            }
+        }
+    }
+
+    let inspected = if total == 0 {
+        "but the collection is empty".to_string()
+    } else {
+        let mut inspected = format!(
+            "but none of {} items did. Inspected items:\n\n{}",
+            total,
+            sample.join("\n").trim_end()
+        );
+        if total > sample.len() {
+            inspected.push_str(&format!("\n... and {} more", total - sample.len()));
+        }
+        inspected
+    };
+
+    Some(format!(
+        "
+Expected at least one item of `{collection_desc}` to satisfy `{predicate_desc}`,
+{inspected}
+",
+        collection_desc = "Collection".red(),
+        predicate_desc = "Predicate".green(),
+        inspected = inspected,
+    ))
+}
@@ -0,0 +1,178 @@
+use crate::string_diff::colored_diff;
+use colored::*;
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// Result of waiting for a message on a channel
+pub enum Received<T> {
+    Message(T),
+    Timeout,
+    Disconnected,
+}
+
+/// Receiving end of a channel that [assert_receives!](crate::assert_receives) and
+/// [assert_no_message!](crate::assert_no_message) can wait on.
+/// Implemented for `std::sync::mpsc::Receiver` and, with `crossbeam` feature enabled,
+/// for `crossbeam_channel::Receiver`.
+pub trait MessageReceiver {
+    type Message;
+
+    /// Block until a message arrives or the timeout elapses
+    fn recv_timeout(&self, timeout: Duration) -> Received<Self::Message>;
+    /// Take a message that is already queued without blocking
+    fn try_recv(&self) -> Option<Self::Message>;
+}
+
+impl<T> MessageReceiver for std::sync::mpsc::Receiver<T> {
+    type Message = T;
+
+    fn recv_timeout(&self, timeout: Duration) -> Received<T> {
+        use std::sync::mpsc::RecvTimeoutError;
+        match std::sync::mpsc::Receiver::recv_timeout(self, timeout) {
+            Ok(message) => Received::Message(message),
+            Err(RecvTimeoutError::Timeout) => Received::Timeout,
+            Err(RecvTimeoutError::Disconnected) => Received::Disconnected,
+        }
+    }
+
+    fn try_recv(&self) -> Option<T> {
+        std::sync::mpsc::Receiver::try_recv(self).ok()
+    }
+}
+
+#[cfg(feature = "crossbeam")]
+impl<T> MessageReceiver for crossbeam_channel::Receiver<T> {
+    type Message = T;
+
+    fn recv_timeout(&self, timeout: Duration) -> Received<T> {
+        use crossbeam_channel::RecvTimeoutError;
+        match crossbeam_channel::Receiver::recv_timeout(self, timeout) {
+            Ok(message) => Received::Message(message),
+            Err(RecvTimeoutError::Timeout) => Received::Timeout,
+            Err(RecvTimeoutError::Disconnected) => Received::Disconnected,
+        }
+    }
+
+    fn try_recv(&self) -> Option<T> {
+        crossbeam_channel::Receiver::try_recv(self).ok()
+    }
+}
+
+impl<R: MessageReceiver + ?Sized> MessageReceiver for &R {
+    type Message = R::Message;
+
+    fn recv_timeout(&self, timeout: Duration) -> Received<Self::Message> {
+        (**self).recv_timeout(timeout)
+    }
+
+    fn try_recv(&self) -> Option<Self::Message> {
+        (**self).try_recv()
+    }
+}
+
+pub fn assert_receives<R, T>(receiver: &R, expected: T, timeout: Duration) -> Option<String>
+where
+    R: MessageReceiver<Message = T>,
+    T: Debug + PartialEq,
+{
+    let failure = match receiver.recv_timeout(timeout) {
+        Received::Message(message) if message == expected => return None,
+        Received::Message(message) => {
+            let diff = colored_diff(&format!("{:#?}", message), &format!("{:#?}", expected))
+                .unwrap_or_else(|| "no visual difference between values".to_string());
+            format!(
+                "but received a different message.\n\n`{received_desc}` - `{expected_desc}`:\n{diff}",
+                received_desc = "Received".red(),
+                expected_desc = "Expected".green(),
+                diff = diff.trim_end_matches('\n'),
+            )
+        }
+        Received::Timeout => "but nothing arrived before the deadline".to_string(),
+        Received::Disconnected => "but the channel was disconnected".to_string(),
+    };
+
+    Some(format!(
+        "Expected `{receiver_desc}` to receive `{expected_desc}` within {timeout:?},
+{failure}
+{remaining}",
+        receiver_desc = "Receiver".yellow(),
+        expected_desc = "Expected".green(),
+        timeout = timeout,
+        failure = failure,
+        remaining = drain(receiver),
+    ))
+}
+
+pub fn assert_no_message<R>(receiver: &R, timeout: Duration) -> Option<String>
+where
+    R: MessageReceiver,
+    R::Message: Debug,
+{
+    let message = match receiver.recv_timeout(timeout) {
+        Received::Message(message) => message,
+        Received::Timeout | Received::Disconnected => return None,
+    };
+
+    Some(format!(
+        "Expected `{receiver_desc}` to receive no messages within {timeout:?},
+but received:
+{message}
+{remaining}",
+        receiver_desc = "Receiver".yellow(),
+        timeout = timeout,
+        message = format!("{:#?}", message).red(),
+        remaining = drain(receiver),
+    ))
+}
+
+/// Number of remaining messages printed in a failure message
+const MAX_SHOWN_MESSAGES: usize = 20;
+/// Number of remaining messages taken from the channel at most. A producer
+/// can keep sending messages forever, so the channel can't be drained fully.
+const MAX_COUNTED_MESSAGES: usize = 1000;
+
+/// Take what is left in the channel, so it's visible in the failure message
+fn drain<R>(receiver: &R) -> String
+where
+    R: MessageReceiver,
+    R::Message: Debug,
+{
+    let mut incoming = std::iter::from_fn(|| receiver.try_recv()).take(MAX_COUNTED_MESSAGES);
+    let shown = incoming
+        .by_ref()
+        .take(MAX_SHOWN_MESSAGES)
+        .map(|message| format!("{:#?}", message))
+        .collect::<Vec<_>>();
+    let not_shown = incoming.count();
+
+    if shown.is_empty() {
+        return String::new();
+    }
+
+    let mut messages = shown
+        .iter()
+        .enumerate()
+        .map(|(index, message)| format!("[{}] {}", index, message.red()))
+        .collect::<Vec<_>>();
+
+    // the channel wasn't necessarily empty after taking this many messages
+    let at_least = if shown.len() + not_shown == MAX_COUNTED_MESSAGES {
+        "at least "
+    } else {
+        ""
+    };
+    if not_shown > 0 {
+        messages.push(
+            format!("… and {}{} more", at_least, not_shown)
+                .dimmed()
+                .to_string(),
+        );
+    }
+
+    format!(
+        "\nRemaining queued messages ({at_least}{count}):\n{messages}\n",
+        at_least = at_least,
+        count = shown.len() + not_shown,
+        messages = messages.join("\n"),
+    )
+}
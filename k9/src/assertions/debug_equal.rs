@@ -0,0 +1,42 @@
+use crate::MultilineString;
+use std::fmt::Debug;
+
+pub fn assert_debug_equal<T: Debug + ?Sized>(value: &T, expected: &str) -> Option<String> {
+    // Multiline expectations are compared against the pretty printed value
+    let actual = if expected.contains('\n') {
+        format!("{:#?}", value)
+    } else {
+        format!("{:?}", value)
+    };
+
+    assert_text_equal(actual, expected)
+}
+
+/// Compare two rendered strings and print a line by line difference.
+///
+/// Multiline expected strings are usually written as
+/// ```text
+/// "
+/// A {
+///     b: 1,
+/// }
+/// "
+/// ```
+/// so, same as in snapshots, the leading newline and the newline before the closing
+/// quote are ignored (the latter only if the actual string doesn't end with one).
+pub fn assert_text_equal(actual: String, expected: &str) -> Option<String> {
+    let mut expected = expected;
+    if expected.contains('\n') {
+        expected = expected.strip_prefix('\n').unwrap_or(expected);
+        if !actual.ends_with('\n') {
+            expected = expected.strip_suffix('\n').unwrap_or(expected);
+        }
+    }
+
+    let fail = actual != expected;
+    crate::assertions::equal::assert_equal(
+        MultilineString(actual),
+        MultilineString::new(expected),
+        fail,
+    )
+}
@@ -0,0 +1,55 @@
+use super::equal::assert_equal;
+use colored::*;
+use std::fmt::Debug;
+
+pub fn assert_deterministic<F, T>(runs: usize, mut f: F) -> Option<String>
+where
+    F: FnMut() -> T,
+    T: Debug + PartialEq,
+{
+    let results = (0..runs).map(|_| f()).collect::<Vec<_>>();
+    compare_results(&results, "")
+}
+
+/// Same as [assert_deterministic], but every run happens on its own thread
+/// and all of them run at the same time.
+pub fn assert_deterministic_threaded<F, T>(runs: usize, f: F) -> Option<String>
+where
+    F: Fn() -> T + Sync,
+    T: Debug + PartialEq + Send,
+{
+    let results = std::thread::scope(|scope| {
+        let handles = (0..runs).map(|_| scope.spawn(&f)).collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect::<Vec<_>>()
+    });
+    compare_results(&results, " on separate threads")
+}
+
+fn compare_results<T: Debug + PartialEq>(results: &[T], how: &str) -> Option<String> {
+    let first = results.first()?;
+    let (index, different) = results
+        .iter()
+        .enumerate()
+        .find(|(_, result)| *result != first)?;
+
+    let diff = assert_equal(first, different, true)
+        .unwrap_or_else(|| "no visual difference between values".to_string());
+
+    Some(format!(
+        "Expected {closure_desc} to return equal results on all {runs} runs{how},
+but run {index} returned a different result than run 1
+{diff}",
+        closure_desc = "closure".yellow(),
+        runs = results.len(),
+        how = how,
+        index = index + 1,
+        diff = diff,
+    ))
+}
@@ -0,0 +1,6 @@
+use crate::assertions::debug_equal::assert_text_equal;
+use std::fmt::Display;
+
+pub fn assert_display_equal<T: Display + ?Sized>(value: &T, expected: &str) -> Option<String> {
+    assert_text_equal(value.to_string(), expected)
+}
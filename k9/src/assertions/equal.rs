@@ -1,5 +1,8 @@
 use crate::string_diff::colored_diff;
+use crate::structural_diff;
+use crate::utils::strip_ansi_escapes;
 use colored::*;
+use std::fmt::Write;
 
 /// Trait used to turn types into a string we can then diff to show pretty
 /// assertions. This allows customizations for certain types to make the
@@ -75,20 +78,111 @@ pub fn assert_equal<
     fail: bool,
 ) -> Option<String> {
     if fail {
-        let diff_string = colored_diff(&left.format(), &right.format())
-            .unwrap_or_else(|| "no visual difference between values".to_string());
-
-        let message = format!(
-            "
-Expected `{left_desc}` to equal `{right_desc}`:
-{diff_string}",
+        let header = format!(
+            "Expected `{left_desc}` to equal `{right_desc}`:",
             left_desc = "Left".red(),
             right_desc = "Right".green(),
-            diff_string = &diff_string
         );
-
-        Some(message)
+        Some(equal_message(&header, &left.format(), &right.format()))
     } else {
         None
     }
 }
+
+/// Failure message of assertions comparing two formatted values: the header,
+/// the list of structural differences and the diff. Values that don't fit into
+/// [max_message_size](crate::config::max_message_size) are truncated
+pub(crate) fn equal_message(header: &str, left: &str, right: &str) -> String {
+    let diff_string = colored_diff(left, right)
+        .unwrap_or_else(|| "no visual difference between values".to_string());
+    let differences = structural_diff::describe(left, right).unwrap_or_default();
+
+    let message = format!(
+        "
+{header}
+{differences}{diff_string}",
+        header = header,
+        differences = differences,
+        diff_string = &diff_string
+    );
+
+    if visible_len(&message) <= crate::config::max_message_size() {
+        return message;
+    }
+    truncated_message(header, left, right, &differences, &diff_string)
+}
+
+/// Number of characters of colored text as it's printed
+fn visible_len(s: &str) -> usize {
+    strip_ansi_escapes(s).chars().count()
+}
+
+/// Failure message for values that are too large to print in full. It only has
+/// as many lines of the diff as fit into [max_message_size](crate::config::max_message_size),
+/// and the full values and diff are written to files instead
+fn truncated_message(
+    header: &str,
+    left: &str,
+    right: &str,
+    differences: &str,
+    diff_string: &str,
+) -> String {
+    let mut message = format!(
+        "
+{header}
+{differences}
+Values are too large to print in full, `{left_desc}` has {left_size} and `{right_desc}` has {right_size}
+",
+        header = header,
+        left_desc = "Left".red(),
+        right_desc = "Right".green(),
+        differences = differences,
+        left_size = describe_size(left),
+        right_size = describe_size(right),
+    );
+
+    let written = crate::artifacts::write(&[
+        ("left.txt", left),
+        ("right.txt", right),
+        ("diff.txt", &strip_ansi_escapes(diff_string)),
+    ]);
+    let mut files = String::new();
+    match written {
+        Ok(paths) => {
+            writeln!(files, "\nFull values and diff were written to:").unwrap();
+            for path in paths {
+                writeln!(files, "  {}", path.display().to_string().yellow()).unwrap();
+            }
+        }
+        Err(error) => writeln!(files, "\nFailed to write full values: {:#}", error).unwrap(),
+    }
+
+    let budget = crate::config::max_message_size()
+        .saturating_sub(visible_len(&message) + visible_len(&files));
+    let mut used = 0;
+    let mut lines = diff_string.lines();
+    for line in lines.by_ref() {
+        used += visible_len(line) + 1;
+        if used > budget {
+            break;
+        }
+        writeln!(message, "{}", line).unwrap();
+    }
+    // `lines` is past the line that didn't fit
+    let omitted = lines.count() + usize::from(used > budget);
+    if omitted > 0 {
+        let noun = if omitted == 1 { "line" } else { "lines" };
+        let omitted = format!("⋯ {} more {} of the diff not shown ⋯", omitted, noun);
+        writeln!(message, "  {}", omitted.dimmed()).unwrap();
+    }
+    message.push_str(&files);
+    message
+}
+
+fn describe_size(value: &str) -> String {
+    format!(
+        "{} lines ({} characters)",
+        value.lines().count(),
+        value.chars().count()
+    )
+}
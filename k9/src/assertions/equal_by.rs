@@ -0,0 +1,81 @@
+use super::equal::{equal_message, FormattableForComparison};
+use colored::*;
+use std::fmt::Debug;
+
+pub fn assert_equal_by<T1, T2, F>(left: &T1, right: &T2, comparator: F) -> Option<String>
+where
+    T1: FormattableForComparison + ?Sized,
+    T2: FormattableForComparison + ?Sized,
+    F: FnOnce(&T1, &T2) -> bool,
+{
+    if comparator(left, right) {
+        return None;
+    }
+
+    let header = format!(
+        "Expected `{left_desc}` to equal `{right_desc}` by `{comparator_desc}`:",
+        left_desc = "Left".red(),
+        right_desc = "Right".green(),
+        comparator_desc = "Comparator".yellow(),
+    );
+    Some(equal_message(&header, &left.format(), &right.format()))
+}
+
+pub fn assert_equal_by_key<T, F, K>(left: &T, right: &T, key: F) -> Option<String>
+where
+    T: FormattableForComparison + ?Sized,
+    F: Fn(&T) -> K,
+    K: Debug + PartialEq,
+{
+    let (left_key, right_key) = (key(left), key(right));
+    if left_key == right_key {
+        return None;
+    }
+
+    let header = format!(
+        "Expected `{left_desc}` to equal `{right_desc}` by `{key_desc}`:
+
+{keys}",
+        left_desc = "Left".red(),
+        right_desc = "Right".green(),
+        key_desc = "Key".yellow(),
+        keys = side_by_side(
+            ("Left key", &format!("{:#?}", left_key)),
+            ("Right key", &format!("{:#?}", right_key)),
+        ),
+    );
+    Some(equal_message(&header, &left.format(), &right.format()))
+}
+
+/// Render two `{:#?}` values in columns, so the parts that decided the comparison
+/// can be read next to each other
+fn side_by_side((left_title, left): (&str, &str), (right_title, right): (&str, &str)) -> String {
+    let left_lines = std::iter::once(left_title)
+        .chain(left.lines())
+        .collect::<Vec<_>>();
+    let right_lines = std::iter::once(right_title)
+        .chain(right.lines())
+        .collect::<Vec<_>>();
+    let width = left_lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    (0..left_lines.len().max(right_lines.len()))
+        .map(|i| {
+            let left_line = left_lines.get(i).copied().unwrap_or("");
+            let right_line = right_lines.get(i).copied().unwrap_or("");
+            let padded = format!("{:width$}", left_line, width = width);
+            let (left_line, right_line) = if i == 0 {
+                (padded.bold(), right_line.bold())
+            } else {
+                (padded.red(), right_line.green())
+            };
+            format!("{} │ {}", left_line, right_line)
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
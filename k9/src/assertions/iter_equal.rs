@@ -0,0 +1,84 @@
+use crate::string_diff::colored_diff;
+use colored::*;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+/// Number of equal items printed before the first difference
+const WINDOW_SIZE: usize = 3;
+
+pub fn assert_iter_equal<L, R>(left: L, right: R) -> Option<String>
+where
+    L: IntoIterator,
+    R: IntoIterator,
+    L::Item: Debug + PartialEq<R::Item>,
+    R::Item: Debug,
+{
+    let mut left = left.into_iter();
+    let mut right = right.into_iter();
+    // Items are only formatted if the assertion fails
+    let mut window: VecDeque<(L::Item, R::Item)> = VecDeque::with_capacity(WINDOW_SIZE);
+    let mut index = 0;
+
+    let (left_item, right_item) = loop {
+        match (left.next(), right.next()) {
+            (None, None) => return None,
+            (Some(l), Some(r)) if l == r => {
+                if window.len() == WINDOW_SIZE {
+                    window.pop_front();
+                }
+                window.push_back((l, r));
+                index += 1;
+            }
+            (l, r) => break (l, r),
+        }
+    };
+
+    let reason = match (&left_item, &right_item) {
+        (None, _) => format!("but `{}` ran out of items", "Left".red()),
+        (_, None) => format!("but `{}` ran out of items", "Right".green()),
+        _ => "but items are different".to_string(),
+    };
+
+    let window_start = index - window.len();
+    let mut left_str = String::new();
+    let mut right_str = String::new();
+
+    if window_start > 0 {
+        left_str.push_str("...\n");
+        right_str.push_str("...\n");
+    }
+    for (i, (l, r)) in window.iter().enumerate() {
+        left_str.push_str(&format_item(window_start + i, l));
+        left_str.push('\n');
+        right_str.push_str(&format_item(window_start + i, r));
+        right_str.push('\n');
+    }
+    left_str.push_str(&format_last_item(index, left_item));
+    right_str.push_str(&format_last_item(index, right_item));
+
+    let diff_string = colored_diff(&left_str, &right_str)
+        .unwrap_or_else(|| "no visual difference between values".to_string());
+
+    Some(format!(
+        "
+Expected `{left_desc}` to equal `{right_desc}` at index {index},
+{reason}:
+{diff_string}",
+        left_desc = "Left".red(),
+        right_desc = "Right".green(),
+        index = index,
+        reason = reason,
+        diff_string = diff_string,
+    ))
+}
+
+fn format_item<T: Debug>(index: usize, item: &T) -> String {
+    format!("[{}] {:#?}", index, item)
+}
+
+fn format_last_item<T: Debug>(index: usize, item: Option<T>) -> String {
+    match item {
+        Some(item) => format_item(index, &item),
+        None => format!("[{}] <end of iterator>", index),
+    }
+}
@@ -0,0 +1,255 @@
+use colored::*;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+/// A law that doesn't hold for some of the samples. `values` are indices of the
+/// samples that break it, as few of them as possible.
+struct Violation {
+    law: &'static str,
+    explanation: String,
+    values: Vec<usize>,
+}
+
+const NAMES: [&str; 3] = ["a", "b", "c"];
+
+pub fn assert_eq_laws<I>(samples: I) -> Option<String>
+where
+    I: IntoIterator,
+    I::Item: Debug + PartialEq,
+{
+    let samples = samples.into_iter().collect::<Vec<_>>();
+    let violation = find_eq_violation(&samples)?;
+    Some(violation_message("PartialEq", &samples, violation, |_| {
+        None
+    }))
+}
+
+pub fn assert_ord_laws<I>(samples: I) -> Option<String>
+where
+    I: IntoIterator,
+    I::Item: Debug + Ord,
+{
+    let samples = samples.into_iter().collect::<Vec<_>>();
+    let violation = find_eq_violation(&samples).or_else(|| find_ord_violation(&samples))?;
+    Some(violation_message("Ord", &samples, violation, |_| None))
+}
+
+pub fn assert_hash_consistent<I>(samples: I) -> Option<String>
+where
+    I: IntoIterator,
+    I::Item: Debug + Hash + Eq,
+{
+    let samples = samples.into_iter().collect::<Vec<_>>();
+    let hashes = samples.iter().map(hash).collect::<Vec<_>>();
+
+    let violation = pairs(samples.len()).find_map(|(i, j)| {
+        if samples[i] == samples[j] && hashes[i] != hashes[j] {
+            Some(Violation {
+                law: "hash consistency",
+                explanation: "`a == b` but `hash(a) != hash(b)`".to_string(),
+                values: vec![i, j],
+            })
+        } else {
+            None
+        }
+    })?;
+
+    Some(violation_message("Hash", &samples, violation, |i| {
+        Some(format!("{:#018x}", hashes[i]))
+    }))
+}
+
+fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Indices of all distinct pairs, in the order of the samples
+fn pairs(len: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..len).flat_map(move |i| (i + 1..len).map(move |j| (i, j)))
+}
+
+/// Indices of all (ordered) triples of distinct samples
+fn triples(len: usize) -> impl Iterator<Item = (usize, usize, usize)> {
+    (0..len).flat_map(move |i| {
+        (0..len).flat_map(move |j| {
+            (0..len)
+                .filter(move |k| i != j && j != *k && i != *k)
+                .map(move |k| (i, j, k))
+        })
+    })
+}
+
+fn find_eq_violation<T: PartialEq>(samples: &[T]) -> Option<Violation> {
+    let reflexivity = (0..samples.len()).find_map(|i| {
+        // comparing a value with itself is exactly what's being checked here
+        #[allow(clippy::eq_op)]
+        let reflexive = samples[i] == samples[i];
+        if !reflexive {
+            Some(Violation {
+                law: "reflexivity",
+                explanation: "`a != a`".to_string(),
+                values: vec![i],
+            })
+        } else {
+            None
+        }
+    });
+
+    let symmetry = || {
+        pairs(samples.len()).find_map(|(i, j)| {
+            let (ab, ba) = (samples[i] == samples[j], samples[j] == samples[i]);
+            if ab != ba {
+                Some(Violation {
+                    law: "symmetry",
+                    explanation: format!(
+                        "`a {} b` but `b {} a`",
+                        if ab { "==" } else { "!=" },
+                        if ba { "==" } else { "!=" }
+                    ),
+                    values: vec![i, j],
+                })
+            } else {
+                None
+            }
+        })
+    };
+
+    let transitivity = || {
+        triples(samples.len()).find_map(|(i, j, k)| {
+            if samples[i] == samples[j] && samples[j] == samples[k] && samples[i] != samples[k] {
+                Some(Violation {
+                    law: "transitivity",
+                    explanation: "`a == b` and `b == c` but `a != c`".to_string(),
+                    values: vec![i, j, k],
+                })
+            } else {
+                None
+            }
+        })
+    };
+
+    reflexivity.or_else(symmetry).or_else(transitivity)
+}
+
+fn find_ord_violation<T: Ord>(samples: &[T]) -> Option<Violation> {
+    let consistency = (0..samples.len()).find_map(|i| {
+        let cmp = samples[i].cmp(&samples[i]);
+        if cmp != Ordering::Equal {
+            Some(Violation {
+                law: "reflexivity",
+                explanation: format!("`a.cmp(a)` is `{:?}`", cmp),
+                values: vec![i],
+            })
+        } else {
+            None
+        }
+    });
+
+    let pair_laws = || {
+        pairs(samples.len()).find_map(|(i, j)| {
+            let (a, b) = (&samples[i], &samples[j]);
+            let (ab, ba) = (a.cmp(b), b.cmp(a));
+            let violation = |law, explanation| {
+                Some(Violation {
+                    law,
+                    explanation,
+                    values: vec![i, j],
+                })
+            };
+
+            if ab != ba.reverse() {
+                violation(
+                    "antisymmetry",
+                    format!("`a.cmp(b)` is `{:?}` but `b.cmp(a)` is `{:?}`", ab, ba),
+                )
+            } else if (ab == Ordering::Equal) != (a == b) {
+                violation(
+                    "consistency with `PartialEq`",
+                    format!(
+                        "`a.cmp(b)` is `{:?}` but `a {} b`",
+                        ab,
+                        if a == b { "==" } else { "!=" }
+                    ),
+                )
+            } else if a.partial_cmp(b) != Some(ab) {
+                violation(
+                    "consistency with `PartialOrd`",
+                    format!(
+                        "`a.cmp(b)` is `{:?}` but `a.partial_cmp(b)` is `{:?}`",
+                        ab,
+                        a.partial_cmp(b)
+                    ),
+                )
+            } else {
+                None
+            }
+        })
+    };
+
+    let transitivity = || {
+        triples(samples.len()).find_map(|(i, j, k)| {
+            let (ab, bc, ac) = (
+                samples[i].cmp(&samples[j]),
+                samples[j].cmp(&samples[k]),
+                samples[i].cmp(&samples[k]),
+            );
+            if ab == bc && ac != ab {
+                Some(Violation {
+                    law: "transitivity",
+                    explanation: format!(
+                        "`a.cmp(b)` and `b.cmp(c)` are `{:?}` but `a.cmp(c)` is `{:?}`",
+                        ab, ac
+                    ),
+                    values: vec![i, j, k],
+                })
+            } else {
+                None
+            }
+        })
+    };
+
+    consistency.or_else(pair_laws).or_else(transitivity)
+}
+
+fn violation_message<T: Debug>(
+    trait_name: &str,
+    samples: &[T],
+    violation: Violation,
+    extra: impl Fn(usize) -> Option<String>,
+) -> String {
+    let values = violation
+        .values
+        .iter()
+        .zip(NAMES.iter())
+        .map(|(index, name)| {
+            let extra = extra(*index)
+                .map(|extra| format!(" (hash {})", extra))
+                .unwrap_or_default();
+            format!(
+                "{name} = samples[{index}]{extra}:\n{value}",
+                name = name.yellow(),
+                index = index,
+                extra = extra,
+                value = format!("{:#?}", samples[*index]).red(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!(
+        "Expected `{samples_desc}` to satisfy `{trait_name}` laws,
+but {law} is violated: {explanation}
+
+{values}
+",
+        samples_desc = "Samples".green(),
+        trait_name = trait_name,
+        law = violation.law,
+        explanation = violation.explanation,
+        values = values,
+    )
+}
@@ -0,0 +1,429 @@
+use crate::debug_tree::{push_indent, Node};
+use crate::string_diff::colored_diff;
+use colored::*;
+use std::fmt::Debug;
+
+/// Partial template that a value is matched against.
+/// Usually constructed by [assert_matches_shape!](crate::assert_matches_shape)
+pub enum Shape {
+    /// Struct fields, tuple indexes or map keys. Everything that is not listed is ignored.
+    Fields(Vec<(ShapeKey, Shape)>),
+    /// Sequence that must have exactly this number of items. Every item is matched partially.
+    Items(Vec<Shape>),
+    /// Value compared using its `{:#?}` representation.
+    Value(String),
+    /// Custom matcher, e.g. [any_string()]
+    Matcher(Box<dyn ShapeMatcher>),
+}
+
+pub enum ShapeKey {
+    /// Struct field name or a tuple index. e.g. `name` or `0`
+    Field(String),
+    /// `{:#?}` representation of a map key
+    Key(String),
+}
+
+/// Something that can be put in place of a value in
+/// [assert_matches_shape!](crate::assert_matches_shape) template
+pub trait ShapeMatcher {
+    /// `debug` is the `{:#?}` representation of the value in the matcher position
+    fn matches(&self, debug: &str) -> bool;
+    /// How this matcher is displayed in the failure diff. e.g. `<any string>`
+    fn describe(&self) -> String;
+}
+
+#[derive(Clone)]
+pub struct Any;
+
+impl ShapeMatcher for Any {
+    fn matches(&self, _debug: &str) -> bool {
+        true
+    }
+
+    fn describe(&self) -> String {
+        "<any>".to_string()
+    }
+}
+
+/// Matches any value, as long as the field is present
+pub fn any() -> Any {
+    Any
+}
+
+#[derive(Clone)]
+pub struct AnyString;
+
+impl ShapeMatcher for AnyString {
+    fn matches(&self, debug: &str) -> bool {
+        debug.starts_with('"')
+    }
+
+    fn describe(&self) -> String {
+        "<any string>".to_string()
+    }
+}
+
+/// Matches any `String` or `&str`
+pub fn any_string() -> AnyString {
+    AnyString
+}
+
+#[derive(Clone)]
+pub struct AnyNumber;
+
+impl ShapeMatcher for AnyNumber {
+    fn matches(&self, debug: &str) -> bool {
+        debug.parse::<f64>().is_ok()
+    }
+
+    fn describe(&self) -> String {
+        "<any number>".to_string()
+    }
+}
+
+/// Matches any integer or float
+pub fn any_number() -> AnyNumber {
+    AnyNumber
+}
+
+#[cfg(feature = "regex")]
+#[derive(Clone)]
+pub struct MatchesRegex(regex::Regex);
+
+#[cfg(feature = "regex")]
+impl ShapeMatcher for MatchesRegex {
+    fn matches(&self, debug: &str) -> bool {
+        debug.starts_with('"') && self.0.is_match(&unescape_debug_str(debug))
+    }
+
+    fn describe(&self) -> String {
+        format!("<string matching /{}/>", self.0)
+    }
+}
+
+/// Matches a `String` or `&str` that matches the regular expression
+///
+/// Panics if `regex` is not a valid regular expression
+#[cfg(feature = "regex")]
+pub fn matches_regex(regex: &str) -> MatchesRegex {
+    MatchesRegex(
+        regex::Regex::new(regex)
+            .unwrap_or_else(|e| panic!("matches_regex: invalid regex {:?}: {}", regex, e)),
+    )
+}
+
+/// Turn `"hello\n\"world\""` debug representation back into the original string
+#[cfg(feature = "regex")]
+fn unescape_debug_str(debug: &str) -> String {
+    let inner = &debug[1..debug.len() - 1];
+    let mut result = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('r') => result.push('\r'),
+                Some('t') => result.push('\t'),
+                Some('0') => result.push('\0'),
+                Some(c) => result.push(c),
+                None => result.push('\\'),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+// Template leaves can be either matchers or plain values. Without specialization
+// we pick between the two using autoref-based method resolution.
+// Matchers are resolved first since `MatcherLeaf` is implemented for the reference.
+#[doc(hidden)]
+pub struct ShapeLeaf<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait MatcherLeaf {
+    fn to_shape(&self) -> Shape;
+}
+
+impl<M: ShapeMatcher + Clone + 'static> MatcherLeaf for &ShapeLeaf<'_, M> {
+    fn to_shape(&self) -> Shape {
+        Shape::Matcher(Box::new(self.0.clone()))
+    }
+}
+
+#[doc(hidden)]
+pub trait ValueLeaf {
+    fn to_shape(&self) -> Shape;
+}
+
+impl<T: Debug + ?Sized> ValueLeaf for ShapeLeaf<'_, T> {
+    fn to_shape(&self) -> Shape {
+        Shape::Value(format!("{:#?}", self.0))
+    }
+}
+
+pub fn assert_matches_shape<T: Debug>(value: T, shape: Shape) -> Option<String> {
+    let node = Node::parse(&format!("{:#?}", value));
+    let mut missing = vec![];
+    let (actual, expected) = compare(&shape, &node, "", 0, &mut missing);
+
+    if actual == expected && missing.is_empty() {
+        return None;
+    }
+
+    let diff_string = colored_diff(&actual, &expected)
+        .unwrap_or_else(|| "no visual difference between values".to_string());
+
+    let missing_string = if missing.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "\nFields missing from `{value_desc}`:\n{fields}\n",
+            value_desc = "Value".red(),
+            fields = missing
+                .iter()
+                .map(|path| format!("  {}", path.green()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    };
+
+    Some(format!(
+        "
+Expected `{value_desc}` to match `{shape_desc}`:
+{diff_string}{missing_string}",
+        value_desc = "Value".red(),
+        shape_desc = "Shape".green(),
+        diff_string = diff_string,
+        missing_string = missing_string,
+    ))
+}
+
+/// Walk the template and the value together. Returns the value projected to only
+/// the parts mentioned in the template and the template itself, both rendered in
+/// `{:#?}` style at the given indentation level, so they can be diffed line by line.
+fn compare(
+    shape: &Shape,
+    node: &Node,
+    path: &str,
+    indent: usize,
+    missing: &mut Vec<String>,
+) -> (String, String) {
+    let actual = render_node(node, indent);
+    match (shape, node) {
+        (Shape::Value(value), _) => (actual, render_node(&Node::parse(value), indent)),
+        (Shape::Matcher(matcher), _) => {
+            let expected = if matcher.matches(&node.render()) {
+                actual.clone()
+            } else {
+                matcher.describe()
+            };
+            (actual, expected)
+        }
+        (
+            Shape::Fields(fields),
+            Node::Struct {
+                name,
+                fields: node_fields,
+            },
+        ) => compare_fields(
+            fields,
+            |key| match key {
+                ShapeKey::Field(field) => {
+                    node_fields.iter().find(|(f, _)| f == field).map(|(_, n)| n)
+                }
+                ShapeKey::Key(_) => None,
+            },
+            (&format!("{} {{", name), "}"),
+            path,
+            indent,
+            missing,
+        ),
+        (Shape::Fields(fields), Node::Tuple { name, items }) => compare_fields(
+            fields,
+            |key| match key {
+                ShapeKey::Field(field) => field.parse::<usize>().ok().and_then(|i| items.get(i)),
+                ShapeKey::Key(_) => None,
+            },
+            (&format!("{}(", name), ")"),
+            path,
+            indent,
+            missing,
+        ),
+        (Shape::Fields(fields), Node::Map(entries)) => compare_fields(
+            fields,
+            |key| match key {
+                ShapeKey::Key(key) => {
+                    let key = Node::parse(key);
+                    entries.iter().find(|(k, _)| *k == key).map(|(_, n)| n)
+                }
+                ShapeKey::Field(_) => None,
+            },
+            ("{", "}"),
+            path,
+            indent,
+            missing,
+        ),
+        (Shape::Fields(fields), _) => {
+            for (key, _) in fields {
+                missing.push(key_path(path, key));
+            }
+            (actual, render_shape(shape, indent))
+        }
+        (Shape::Items(items), Node::Seq(node_items)) => {
+            compare_items(items, node_items, ("[", "]"), path, indent, missing)
+        }
+        (Shape::Items(items), Node::Set(node_items)) => {
+            compare_items(items, node_items, ("{", "}"), path, indent, missing)
+        }
+        (
+            Shape::Items(items),
+            Node::Tuple {
+                name,
+                items: node_items,
+            },
+        ) => compare_items(
+            items,
+            node_items,
+            (&format!("{}(", name), ")"),
+            path,
+            indent,
+            missing,
+        ),
+        (Shape::Items(_), _) => (actual, render_shape(shape, indent)),
+    }
+}
+
+fn compare_fields<'a, F>(
+    fields: &[(ShapeKey, Shape)],
+    lookup: F,
+    (open, close): (&str, &str),
+    path: &str,
+    indent: usize,
+    missing: &mut Vec<String>,
+) -> (String, String)
+where
+    F: Fn(&ShapeKey) -> Option<&'a Node>,
+{
+    let mut actual = format!("{}\n", open);
+    let mut expected = actual.clone();
+
+    for (key, field_shape) in fields {
+        let label = key_label(key, indent + 1);
+        let field_path = key_path(path, key);
+        push_indent(&mut expected, indent + 1);
+        expected.push_str(&label);
+        expected.push_str(": ");
+
+        if let Some(field_node) = lookup(key) {
+            let (a, e) = compare(field_shape, field_node, &field_path, indent + 1, missing);
+            push_indent(&mut actual, indent + 1);
+            actual.push_str(&format!("{}: {},\n", label, a));
+            expected.push_str(&e);
+        } else {
+            expected.push_str(&render_shape(field_shape, indent + 1));
+            missing.push(field_path);
+        }
+        expected.push_str(",\n");
+    }
+
+    push_indent(&mut actual, indent);
+    actual.push_str(close);
+    push_indent(&mut expected, indent);
+    expected.push_str(close);
+    (actual, expected)
+}
+
+fn compare_items(
+    items: &[Shape],
+    node_items: &[Node],
+    (open, close): (&str, &str),
+    path: &str,
+    indent: usize,
+    missing: &mut Vec<String>,
+) -> (String, String) {
+    let mut actual = format!("{}\n", open);
+    let mut expected = actual.clone();
+
+    for i in 0..items.len().max(node_items.len()) {
+        let item_path = format!("{}[{}]", path, i);
+        let (a, e) = match (items.get(i), node_items.get(i)) {
+            (Some(item), Some(node)) => {
+                let (a, e) = compare(item, node, &item_path, indent + 1, missing);
+                (Some(a), Some(e))
+            }
+            (Some(item), None) => {
+                missing.push(item_path);
+                (None, Some(render_shape(item, indent + 1)))
+            }
+            (None, Some(node)) => (Some(render_node(node, indent + 1)), None),
+            (None, None) => (None, None),
+        };
+        for (out, rendered) in [(&mut actual, a), (&mut expected, e)] {
+            if let Some(rendered) = rendered {
+                push_indent(out, indent + 1);
+                out.push_str(&rendered);
+                out.push_str(",\n");
+            }
+        }
+    }
+
+    push_indent(&mut actual, indent);
+    actual.push_str(close);
+    push_indent(&mut expected, indent);
+    expected.push_str(close);
+    (actual, expected)
+}
+
+fn render_node(node: &Node, indent: usize) -> String {
+    let mut result = String::new();
+    node.render_into(&mut result, indent);
+    result
+}
+
+/// Render the template by itself, used when there's no matching value to compare it with
+fn render_shape(shape: &Shape, indent: usize) -> String {
+    match shape {
+        Shape::Value(value) => render_node(&Node::parse(value), indent),
+        Shape::Matcher(matcher) => matcher.describe(),
+        Shape::Fields(fields) => {
+            let mut result = "{\n".to_string();
+            for (key, field_shape) in fields {
+                push_indent(&mut result, indent + 1);
+                result.push_str(&format!(
+                    "{}: {},\n",
+                    key_label(key, indent + 1),
+                    render_shape(field_shape, indent + 1)
+                ));
+            }
+            push_indent(&mut result, indent);
+            result.push('}');
+            result
+        }
+        Shape::Items(items) => {
+            let mut result = "[\n".to_string();
+            for item in items {
+                push_indent(&mut result, indent + 1);
+                result.push_str(&format!("{},\n", render_shape(item, indent + 1)));
+            }
+            push_indent(&mut result, indent);
+            result.push(']');
+            result
+        }
+    }
+}
+
+fn key_label(key: &ShapeKey, indent: usize) -> String {
+    match key {
+        ShapeKey::Field(field) => field.clone(),
+        ShapeKey::Key(key) => render_node(&Node::parse(key), indent),
+    }
+}
+
+fn key_path(path: &str, key: &ShapeKey) -> String {
+    match key {
+        ShapeKey::Field(field) => format!("{}.{}", path, field),
+        ShapeKey::Key(key) => format!("{}[{}]", path, Node::parse(key).render()),
+    }
+}
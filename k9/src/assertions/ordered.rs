@@ -0,0 +1,188 @@
+use colored::*;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+/// Operator between two operands of [assert_ordered!](crate::assert_ordered)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Less => ordering == Ordering::Less,
+            Comparison::LessOrEqual => ordering != Ordering::Greater,
+            Comparison::Greater => ordering == Ordering::Greater,
+            Comparison::GreaterOrEqual => ordering != Ordering::Less,
+        }
+    }
+
+    fn operator(self) -> &'static str {
+        match self {
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+        }
+    }
+}
+
+/// Result of comparing two neighbouring operands of the chain
+#[derive(Debug, Clone, Copy)]
+pub struct Link {
+    comparison: Comparison,
+    ordering: Option<Ordering>,
+}
+
+impl Link {
+    fn holds(&self) -> bool {
+        self.ordering
+            .map(|ordering| self.comparison.holds(ordering))
+            .unwrap_or(false)
+    }
+}
+
+/// Compare two operands. The operands don't need to be of the same type,
+/// as long as `left` can be compared to `right`
+pub fn compare<L, R>(left: &L, right: &R, comparison: Comparison) -> Link
+where
+    L: PartialOrd<R> + ?Sized,
+    R: ?Sized,
+{
+    Link {
+        comparison,
+        ordering: left.partial_cmp(right),
+    }
+}
+
+/// `operands` are the stringified expressions together with their values and
+/// `links[i]` is the comparison between `operands[i]` and `operands[i + 1]`
+pub fn assert_ordered(operands: &[(&str, &dyn Debug)], links: &[Link]) -> Option<String> {
+    let failed = links
+        .iter()
+        .enumerate()
+        .filter(|(_, link)| !link.holds())
+        .collect::<Vec<_>>();
+
+    if failed.is_empty() {
+        return None;
+    }
+
+    let exprs = operands
+        .iter()
+        .map(|(expr, _)| tidy_generics(expr))
+        .collect::<Vec<_>>();
+    let mut chain = exprs[0].clone();
+    for (link, expr) in links.iter().zip(&exprs[1..]) {
+        chain.push_str(&format!(" {} {}", link.comparison.operator(), expr));
+    }
+
+    let reasons = failed
+        .iter()
+        .enumerate()
+        .map(|(n, (i, link))| {
+            let (left, right) = (&exprs[*i], &exprs[i + 1]);
+            let reason = match link.ordering {
+                None => format!("`{}` and `{}` can't be compared", left, right),
+                Some(ordering) => format!(
+                    "`{}` is {} `{}`",
+                    left,
+                    match ordering {
+                        Ordering::Less => "lesser than",
+                        Ordering::Equal => "equal to",
+                        Ordering::Greater => "greater than",
+                    },
+                    right
+                ),
+            };
+            format!(
+                "{} `{}` doesn't hold, {}",
+                if n == 0 { "but" } else { "and" },
+                format!("{} {} {}", left, link.comparison.operator(), right).yellow(),
+                reason
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let width = exprs
+        .iter()
+        .map(|expr| expr.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let values = exprs
+        .iter()
+        .zip(operands)
+        .enumerate()
+        .map(|(i, (expr, (_, value)))| {
+            let involved = failed.iter().any(|(link, _)| *link == i || link + 1 == i);
+            let value = format!("{:#?}", value)
+                .replace('\n', &format!("\n{:width$}", "", width = width + 2));
+            format!(
+                "{expr:width$} {value}",
+                expr = format!("{}:", expr),
+                width = width + 1,
+                value = if involved {
+                    value.red()
+                } else {
+                    value.normal()
+                },
+            )
+            .trim_end()
+            .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Some(format!(
+        "Expected `{chain}` to hold,
+{reasons}
+
+{values}
+",
+        chain = chain.yellow(),
+        reasons = reasons,
+        values = values,
+    ))
+}
+
+/// `stringify!` puts spaces around the `<` and `>` of generic arguments that
+/// [assert_ordered!](crate::assert_ordered) had to take apart, e.g. `sum:: < u32> ()`.
+/// Remove them, so the expression reads as written: `sum::<u32>()`
+fn tidy_generics(expr: &str) -> String {
+    let mut result = String::new();
+    let mut depth = 0;
+    let mut chars = expr.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' if result.ends_with("::") && chars.peek() == Some(&'<') => {}
+            ' ' if depth > 0 && (result.ends_with('<') || chars.peek() == Some(&'>')) => {}
+            '<' if depth > 0 || result.is_empty() || result.ends_with("::") => {
+                depth += 1;
+                result.push(c);
+            }
+            // `->` of `Fn(u8) -> u8` doesn't close anything
+            '>' if depth > 0 && !result.ends_with('-') => {
+                depth -= 1;
+                result.push(c);
+                // `Vec::<u8> ::new()` and `sum::<u32> ()`
+                if depth == 0 {
+                    let mut rest = chars.clone();
+                    while rest.peek() == Some(&' ') {
+                        rest.next();
+                    }
+                    if matches!(rest.peek(), Some(':') | Some('(')) {
+                        chars = rest;
+                    }
+                }
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
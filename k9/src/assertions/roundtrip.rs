@@ -0,0 +1,205 @@
+use super::equal::FormattableForComparison;
+use super::snapshot::snapshot_internal;
+use crate::string_diff::colored_diff;
+use crate::MultilineString;
+use anyhow::Context;
+use colored::*;
+#[cfg(any(feature = "serde_json", feature = "bincode"))]
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::{Debug, Display};
+
+/// Serialization format used by [assert_roundtrip!](crate::assert_roundtrip)
+pub trait Format<T> {
+    type Serialized;
+
+    /// Displayed in failure messages, e.g. `json`
+    fn name(&self) -> String;
+    fn serialize(&self, value: &T) -> Result<Self::Serialized, String>;
+    fn deserialize(&self, serialized: &Self::Serialized) -> Result<T, String>;
+    /// Human readable representation of the serialized value, used in failure
+    /// messages and snapshots
+    fn display(&self, serialized: &Self::Serialized) -> String;
+}
+
+/// Pretty printed JSON using `serde_json`
+#[cfg(feature = "serde_json")]
+pub struct Json;
+
+#[cfg(feature = "serde_json")]
+impl<T: Serialize + DeserializeOwned> Format<T> for Json {
+    type Serialized = String;
+
+    fn name(&self) -> String {
+        "json".to_string()
+    }
+
+    fn serialize(&self, value: &T) -> Result<String, String> {
+        serde_json::to_string_pretty(value).map_err(|e| e.to_string())
+    }
+
+    fn deserialize(&self, serialized: &String) -> Result<T, String> {
+        serde_json::from_str(serialized).map_err(|e| e.to_string())
+    }
+
+    fn display(&self, serialized: &String) -> String {
+        serialized.clone()
+    }
+}
+
+/// Compact binary encoding using `bincode`. Displayed as a hex dump.
+#[cfg(feature = "bincode")]
+pub struct Bincode;
+
+/// Number of bytes per line of the hex dump
+#[cfg(feature = "bincode")]
+const BYTES_PER_LINE: usize = 16;
+
+#[cfg(feature = "bincode")]
+impl<T: Serialize + DeserializeOwned> Format<T> for Bincode {
+    type Serialized = Vec<u8>;
+
+    fn name(&self) -> String {
+        "bincode".to_string()
+    }
+
+    fn serialize(&self, value: &T) -> Result<Vec<u8>, String> {
+        bincode::serialize(value).map_err(|e| e.to_string())
+    }
+
+    fn deserialize(&self, serialized: &Vec<u8>) -> Result<T, String> {
+        bincode::deserialize(serialized).map_err(|e| e.to_string())
+    }
+
+    fn display(&self, serialized: &Vec<u8>) -> String {
+        serialized
+            .chunks(BYTES_PER_LINE)
+            .map(|line| {
+                line.iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Any pair of serialization and deserialization functions.
+/// The serialized value is displayed using its `Debug` representation.
+pub struct Custom<S, D> {
+    serialize: S,
+    deserialize: D,
+}
+
+impl<S, D> Custom<S, D> {
+    pub fn new(serialize: S, deserialize: D) -> Self {
+        Self {
+            serialize,
+            deserialize,
+        }
+    }
+}
+
+impl<T, S, D, X, E1, E2> Format<T> for Custom<S, D>
+where
+    S: Fn(&T) -> Result<X, E1>,
+    D: Fn(&X) -> Result<T, E2>,
+    X: Debug,
+    E1: Display,
+    E2: Display,
+{
+    type Serialized = X;
+
+    fn name(&self) -> String {
+        "custom format".to_string()
+    }
+
+    fn serialize(&self, value: &T) -> Result<X, String> {
+        (self.serialize)(value).map_err(|e| e.to_string())
+    }
+
+    fn deserialize(&self, serialized: &X) -> Result<T, String> {
+        (self.deserialize)(serialized).map_err(|e| e.to_string())
+    }
+
+    fn display(&self, serialized: &X) -> String {
+        let debug = format!("{:#?}", serialized);
+        // strings are the most common serialized form, don't show their quotes
+        match debug.strip_prefix('"').and_then(|d| d.strip_suffix('"')) {
+            Some(unquoted) => unquoted.replace("\\n", "\n").replace("\\\"", "\""),
+            None => debug,
+        }
+    }
+}
+
+pub fn assert_roundtrip<T, F>(
+    value: &T,
+    format: F,
+    snapshot: Option<&str>,
+    line: u32,
+    file: &str,
+) -> Option<String>
+where
+    T: Debug + PartialEq,
+    F: Format<T>,
+{
+    let value_desc = "Value".red();
+    let format_name = format.name().yellow();
+
+    let serialized = match format.serialize(value) {
+        Ok(serialized) => serialized,
+        Err(error) => {
+            return Some(format!(
+                "Failed to serialize `{}` as {}:\n{}\n",
+                value_desc, format_name, error
+            ))
+        }
+    };
+    let serialized_str = format.display(&serialized);
+
+    let mut messages = vec![];
+
+    match format.deserialize(&serialized) {
+        Err(error) => messages.push(format!(
+            "Failed to deserialize `{}` back from {}:\n{}\n",
+            value_desc, format_name, error
+        )),
+        Ok(deserialized) => {
+            if *value != deserialized {
+                let diff = colored_diff(&value.format(), &deserialized.format())
+                    .unwrap_or_else(|| "no visual difference between values".to_string());
+                messages.push(format!(
+                    "Expected `{value_desc}` to equal `{deserialized_desc}` after a roundtrip through {format_name}:\n{diff}",
+                    value_desc = value_desc,
+                    deserialized_desc = "Deserialized".green(),
+                    format_name = format_name,
+                    diff = diff,
+                ));
+            }
+        }
+    }
+
+    if let Some(snapshot) = snapshot {
+        let snapshot_message = snapshot_internal(
+            MultilineString(serialized_str.clone()),
+            Some(snapshot),
+            line,
+            file,
+            "assert_roundtrip",
+        )
+        .context("assert_roundtrip!() snapshot failed")
+        .unwrap();
+        messages.extend(snapshot_message);
+    }
+
+    if messages.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "{}\nSerialized {}:\n{}\n",
+        messages.join("\n"),
+        format_name,
+        serialized_str
+    ))
+}
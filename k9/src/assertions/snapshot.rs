@@ -1,6 +1,7 @@
 use crate::snapshot::ast;
 use crate::snapshot::source_code;
 use crate::snapshot::source_code::Range;
+use crate::string_diff::{colored_diff_at, FirstLines};
 use crate::types;
 use anyhow::{Context, Result};
 use colored::*;
@@ -40,20 +41,38 @@ pub fn snapshot<V: Debug>(
     _column: u32,
     file: &str,
 ) -> Option<String> {
-    snapshot_internal(value, snapshot, line, file)
+    snapshot_internal(value, snapshot, line, file, "snapshot")
         .context("snapshot!() macro failed")
         .unwrap()
 }
 
+#[cfg(unix)]
+pub fn snapshot_output(
+    output: crate::CapturedOutput,
+    snapshot: Option<&str>,
+    line: u32,
+    _column: u32,
+    file: &str,
+) -> Option<String> {
+    let value = crate::MultilineString(output.to_string());
+    snapshot_internal(value, snapshot, line, file, "snapshot_output")
+        .context("snapshot_output!() macro failed")
+        .unwrap()
+}
+
 pub fn snapshot_internal<V: Debug>(
     value: V,
     snapshot: Option<&str>,
     line: u32,
     file: &str,
+    macro_name: &str,
 ) -> Result<Option<String>> {
     let value_str = value_to_string(value);
     match (snapshot, crate::config::CONFIG.update_mode) {
-        (Some(snapshot), false) => Ok(snapshot_matching_message(&value_str, snapshot)),
+        (Some(snapshot), false) => {
+            let first_line = literal_first_line(file, line, macro_name);
+            Ok(snapshot_matching_message(&value_str, snapshot, first_line))
+        }
         (None, false) => Ok(Some(empty_snapshot_message(&value_str))),
         (_, true) => {
             let line = line as usize;
@@ -65,15 +84,17 @@ pub fn snapshot_internal<V: Debug>(
             this_file_path.push(file);
 
             if let Some(snapshot) = snapshot {
-                let need_updating = snapshot_matching_message(&value_str, snapshot).is_some();
+                let need_updating = value_str != snapshot;
 
                 if need_updating {
                     let mode = UpdateInlineSnapshotMode::Replace;
-                    schedule_snapshot_update(this_file_path, line, &value_str, mode).unwrap();
+                    schedule_snapshot_update(this_file_path, macro_name, line, &value_str, mode)
+                        .unwrap();
                 }
             } else {
                 let mode = UpdateInlineSnapshotMode::Create;
-                schedule_snapshot_update(this_file_path, line, &value_str, mode).unwrap();
+                schedule_snapshot_update(this_file_path, macro_name, line, &value_str, mode)
+                    .unwrap();
             };
 
             Ok(None)
@@ -151,8 +172,30 @@ where
     f(source_file)
 }
 
-fn snapshot_matching_message(s: &str, snapshot: &str) -> Option<String> {
-    let diff = crate::string_diff::colored_diff(snapshot, s);
+/// Line of the source file the inline snapshot literal starts on, so line numbers
+/// in the diff point to the literal. Falls back to `1` if it can't be found
+fn literal_first_line(file: &str, line: u32, macro_name: &str) -> usize {
+    if !crate::config::diff_line_numbers() {
+        return 1;
+    }
+
+    let find = || -> Result<usize> {
+        let mut path = crate::paths::find_crate_root(file)?;
+        path.push(file);
+        let content = SourceFile::read(&path.display().to_string())?;
+        let range = ast::find_snapshot_literal_range(&content, macro_name, line as usize, true)?;
+        Ok(range.start.line)
+    };
+    find().unwrap_or(1)
+}
+
+fn snapshot_matching_message(s: &str, snapshot: &str, first_line: usize) -> Option<String> {
+    // Snapshot is on the left, so left line numbers are the source lines of the literal
+    let first_lines = FirstLines {
+        left: first_line,
+        right: 1,
+    };
+    let diff = colored_diff_at(snapshot, s, first_lines);
 
     diff.map(|diff| {
         format!(
@@ -191,6 +234,7 @@ Received value:
 
 fn schedule_snapshot_update(
     file_path: PathBuf,
+    macro_name: &str,
     original_line_num: usize,
     to_add: &str,
     mode: UpdateInlineSnapshotMode,
@@ -200,7 +244,7 @@ fn schedule_snapshot_update(
     with_source_file(&file_path.display().to_string(), |file| {
         let range = ast::find_snapshot_literal_range(
             &file.content,
-            "snapshot",
+            macro_name,
             original_line_num,
             mode == UpdateInlineSnapshotMode::Replace,
         )
@@ -226,7 +270,7 @@ fn update_inline_snapshots(mut file: SourceFile) -> Result<()> {
     let content = file.content.clone();
 
     let mut updates = file.updates.iter().collect::<Vec<_>>();
-    updates.sort_by(|a, b| a.range.start.line.cmp(&b.range.start.line));
+    updates.sort_by_key(|a| a.range.start.line);
 
     let parts = source_code::split_by_ranges(content, updates.iter().map(|u| &u.range).collect());
 
@@ -0,0 +1,107 @@
+use crate::string_diff::colored_diff;
+use colored::*;
+
+/// Transformations applied to both strings before comparing them
+/// with [assert_str_equal!](crate::assert_str_equal).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Normalize {
+    /// Replace `\r\n` line endings with `\n`
+    Crlf,
+    /// Replace every run of spaces and tabs with a single space
+    CollapseWhitespace,
+    /// Remove leading and trailing whitespace of the whole string
+    Trim,
+    /// Ignore newlines at the end of the string
+    TrailingNewline,
+    /// Compare case insensitively
+    Case,
+}
+
+impl Normalize {
+    fn name(&self) -> &'static str {
+        match self {
+            Normalize::Crlf => "crlf",
+            Normalize::CollapseWhitespace => "collapse_whitespace",
+            Normalize::Trim => "trim",
+            Normalize::TrailingNewline => "trailing_newline",
+            Normalize::Case => "case",
+        }
+    }
+
+    fn apply(&self, s: String) -> String {
+        match self {
+            Normalize::Crlf => s.replace("\r\n", "\n"),
+            Normalize::CollapseWhitespace => {
+                let mut result = String::with_capacity(s.len());
+                let mut in_whitespace = false;
+                for c in s.chars() {
+                    if c == ' ' || c == '\t' {
+                        if !in_whitespace {
+                            result.push(' ');
+                        }
+                        in_whitespace = true;
+                    } else {
+                        result.push(c);
+                        in_whitespace = false;
+                    }
+                }
+                result
+            }
+            Normalize::Trim => s.trim().to_string(),
+            Normalize::TrailingNewline => s.trim_end_matches(['\r', '\n']).to_string(),
+            Normalize::Case => s.to_lowercase(),
+        }
+    }
+}
+
+/// Normalizations are always applied in the order they are declared in [Normalize],
+/// regardless of the order they were passed in.
+pub fn normalize(s: &str, normalizations: &[Normalize]) -> String {
+    let mut normalizations = normalizations.to_vec();
+    normalizations.sort();
+    normalizations.dedup();
+    normalizations
+        .iter()
+        .fold(s.to_string(), |s, normalization| normalization.apply(s))
+}
+
+pub fn assert_str_equal<L: AsRef<str> + ?Sized, R: AsRef<str> + ?Sized>(
+    left: &L,
+    right: &R,
+    normalizations: &[Normalize],
+) -> Option<String> {
+    let (left, right) = (left.as_ref(), right.as_ref());
+
+    if normalize(left, normalizations) == normalize(right, normalizations) {
+        return None;
+    }
+
+    let normalized_desc = if normalizations.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " after normalizing with [{}]",
+            normalizations
+                .iter()
+                .map(|n| n.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+                .yellow()
+        )
+    };
+
+    // The difference is always shown for the original strings, since that's
+    // what the code under test actually produced
+    let diff_string = colored_diff(left, right)
+        .unwrap_or_else(|| "no visual difference between values".to_string());
+
+    Some(format!(
+        "
+Expected `{left_desc}` to equal `{right_desc}`{normalized_desc}:
+{diff_string}",
+        left_desc = "Left".red(),
+        right_desc = "Right".green(),
+        normalized_desc = normalized_desc,
+        diff_string = diff_string,
+    ))
+}
@@ -0,0 +1,258 @@
+use lazy_static::lazy_static;
+use std::any::Any;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::panic::AssertUnwindSafe;
+use std::sync::Mutex;
+
+lazy_static! {
+    /// File descriptors are shared by the whole process, so only one capture
+    /// can be active at a time.
+    static ref CAPTURE_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// Stack size of the thread that runs the captured closure
+const THREAD_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Everything that was written to `stdout` and `stderr` during [capture_output]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapturedOutput {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Renders only non empty streams, each one under its own header.
+impl std::fmt::Display for CapturedOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let streams = [("stdout", &self.stdout), ("stderr", &self.stderr)];
+        let mut needs_newline = false;
+        for (name, content) in streams.iter().filter(|(_, content)| !content.is_empty()) {
+            if needs_newline {
+                writeln!(f)?;
+            }
+            write!(f, "{}:\n{}", name, content)?;
+            needs_newline = !content.ends_with('\n');
+        }
+        Ok(())
+    }
+}
+
+/// Run the closure and return everything it wrote to file descriptors `1` and `2`.
+///
+/// Since the redirection happens on the file descriptor level, this captures output of
+/// `println!`, `eprintln!`, direct writes to [std::io::stdout] and output of C libraries.
+///
+/// The test harness intercepts `println!` calls made from test threads, so the closure
+/// runs on a separate OS thread for the duration of the capture. Captures are serialized
+/// with a global lock, which means `capture_output` calls can't be nested.
+///
+/// ```
+/// let output = k9::capture_output(|| {
+///     println!("hello");
+///     eprintln!("world");
+/// });
+///
+/// k9::assert_equal!(output.stdout, "hello\n");
+/// k9::assert_equal!(output.stderr, "world\n");
+/// ```
+pub fn capture_output<F: FnOnce() + Send>(f: F) -> CapturedOutput {
+    let _lock = CAPTURE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    flush_all();
+    let stdout = Redirect::new(libc::STDOUT_FILENO)
+        .unwrap_or_else(|e| panic!("Failed to redirect stdout: {}", e));
+    let stderr = match Redirect::new(libc::STDERR_FILENO) {
+        Ok(stderr) => stderr,
+        Err(e) => {
+            // Panic messages go to stderr, which isn't redirected yet,
+            // but stdout has to be restored first
+            drop(stdout);
+            panic!("Failed to redirect stderr: {}", e);
+        }
+    };
+
+    let result = run_on_separate_thread(f);
+
+    flush_all();
+    let (stdout, stderr) = (stdout.restore(), stderr.restore());
+    // Nothing is redirected anymore, so it's safe to panic
+    let output = CapturedOutput {
+        stdout: stdout.expect("Failed to read captured stdout"),
+        stderr: stderr.expect("Failed to read captured stderr"),
+    };
+    let panic = result.unwrap_or_else(|e| panic!("{}", e));
+
+    if let Some(panic) = panic {
+        // Don't swallow the output (and the panic message), it's probably
+        // the only clue why the closure panicked
+        print!("{}", output.stdout);
+        eprint!("{}", output.stderr);
+        std::panic::resume_unwind(panic);
+    }
+
+    output
+}
+
+fn flush_all() {
+    std::io::stdout().flush().ok();
+    std::io::stderr().flush().ok();
+    unsafe {
+        libc::fflush(std::ptr::null_mut());
+    }
+}
+
+/// Temporarily points a file descriptor to an anonymous temporary file.
+/// The file descriptor is restored by [Redirect::restore], or when it's dropped
+/// if something panics in between, so output of the rest of the process isn't lost
+struct Redirect {
+    fd: RawFd,
+    /// Duplicate of the original file descriptor, `None` once it's restored
+    saved_fd: Option<RawFd>,
+    file: File,
+}
+
+impl Redirect {
+    fn new(fd: RawFd) -> std::io::Result<Self> {
+        unsafe {
+            let tmp = libc::tmpfile();
+            if tmp.is_null() {
+                return Err(std::io::Error::last_os_error());
+            }
+            let file_fd = libc::dup(libc::fileno(tmp));
+            let error = std::io::Error::last_os_error();
+            libc::fclose(tmp);
+            if file_fd < 0 {
+                return Err(error);
+            }
+            let file = File::from_raw_fd(file_fd);
+
+            let saved_fd = libc::dup(fd);
+            if saved_fd < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if libc::dup2(file_fd, fd) < 0 {
+                let error = std::io::Error::last_os_error();
+                libc::close(saved_fd);
+                return Err(error);
+            }
+
+            Ok(Self {
+                fd,
+                saved_fd: Some(saved_fd),
+                file,
+            })
+        }
+    }
+
+    /// Point the file descriptor back to where it was and return what was written to it
+    fn restore(mut self) -> std::io::Result<String> {
+        self.reset();
+
+        let mut bytes = vec![];
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.read_to_end(&mut bytes)?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn reset(&mut self) {
+        if let Some(saved_fd) = self.saved_fd.take() {
+            unsafe {
+                libc::dup2(saved_fd, self.fd);
+                libc::close(saved_fd);
+            }
+        }
+    }
+}
+
+impl Drop for Redirect {
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+struct ThreadData<'a> {
+    f: Option<Box<dyn FnOnce() + Send + 'a>>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+extern "C" fn thread_main(data: *mut libc::c_void) -> *mut libc::c_void {
+    let data = unsafe { &mut *(data as *mut ThreadData) };
+    if let Some(f) = data.f.take() {
+        data.panic = std::panic::catch_unwind(AssertUnwindSafe(f)).err();
+    }
+    std::ptr::null_mut()
+}
+
+/// Threads spawned with [std::thread] inherit the test harness output capturing,
+/// so we create a bare pthread instead. The thread is always joined before returning,
+/// which makes it safe to borrow from the caller's stack.
+fn run_on_separate_thread<F: FnOnce() + Send>(f: F) -> Result<Option<Box<dyn Any + Send>>, String> {
+    let mut data = ThreadData {
+        f: Some(Box::new(f)),
+        panic: None,
+    };
+
+    // Output is redirected while this runs, so errors are returned rather than
+    // panicked with, otherwise the panic message would end up in the captured output
+    unsafe {
+        let mut attr: libc::pthread_attr_t = std::mem::zeroed();
+        let result = libc::pthread_attr_init(&mut attr);
+        if result != 0 {
+            return Err(format!(
+                "Failed to initialize thread attributes: {}",
+                result
+            ));
+        }
+        let result = libc::pthread_attr_setstacksize(&mut attr, THREAD_STACK_SIZE);
+        if result != 0 {
+            libc::pthread_attr_destroy(&mut attr);
+            return Err(format!("Failed to set thread stack size: {}", result));
+        }
+
+        let mut thread: libc::pthread_t = std::mem::zeroed();
+        let result = libc::pthread_create(
+            &mut thread,
+            &attr,
+            thread_main,
+            &mut data as *mut ThreadData as *mut libc::c_void,
+        );
+        libc::pthread_attr_destroy(&mut attr);
+        if result != 0 {
+            return Err(format!(
+                "Failed to spawn a thread for output capturing: {}",
+                result
+            ));
+        }
+        libc::pthread_join(thread, std::ptr::null_mut());
+    }
+
+    Ok(data.panic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Device and inode of the file a file descriptor points to
+    fn file_id(fd: RawFd) -> (u64, u64) {
+        unsafe {
+            let mut stat: libc::stat = std::mem::zeroed();
+            assert_eq!(libc::fstat(fd, &mut stat), 0);
+            (stat.st_dev as u64, stat.st_ino as u64)
+        }
+    }
+
+    #[test]
+    fn restores_on_drop() {
+        let _lock = CAPTURE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let original = file_id(libc::STDERR_FILENO);
+
+        let redirect = Redirect::new(libc::STDERR_FILENO).unwrap();
+        let redirected = file_id(libc::STDERR_FILENO);
+        drop(redirect);
+
+        assert_ne!(redirected, original);
+        assert_eq!(file_id(libc::STDERR_FILENO), original);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::Snap;
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::sync::Once;
+
+thread_local! {
+    static SINK: RefCell<Option<Snap>> = const { RefCell::new(None) };
+}
+
+static INSTALL_LOGGER: Once = Once::new();
+
+/// `log` only supports a single global logger, so it's installed once and
+/// forwards records to whatever sink is active on the current thread.
+struct CapturingLogger;
+
+static LOGGER: CapturingLogger = CapturingLogger;
+
+impl log::Log for CapturingLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        SINK.with(|sink| sink.borrow().is_some())
+    }
+
+    fn log(&self, record: &log::Record) {
+        SINK.with(|sink| {
+            if let Some(sink) = &*sink.borrow() {
+                sink.pushln(render_record(record));
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Run the closure, appending every [log](https://docs.rs/log) record emitted on the
+/// current thread to `sink`, one line per record: level, target, message and key values.
+///
+/// Fails if a different logger was already installed using `log::set_logger`.
+///
+/// ```
+/// let logs = k9::Snap::new();
+/// k9::capture_log(&logs, || {
+///     log::info!("starting");
+///     log::warn!(attempt = 2; "retrying");
+/// });
+///
+/// k9::snapshot!(
+///     logs.to_string(),
+///     "
+/// INFO rust_out: starting
+/// WARN rust_out: retrying attempt=2
+///
+/// "
+/// );
+/// ```
+pub fn capture_log<F: FnOnce() -> T, T>(sink: &Snap, f: F) -> T {
+    INSTALL_LOGGER.call_once(|| {
+        log::set_logger(&LOGGER)
+            .expect("k9::capture_log() can't be used when a different logger is installed");
+        log::set_max_level(log::LevelFilter::Trace);
+    });
+
+    struct RestoreSink(Option<Snap>);
+
+    impl Drop for RestoreSink {
+        fn drop(&mut self) {
+            SINK.with(|sink| *sink.borrow_mut() = self.0.take());
+        }
+    }
+
+    let previous = SINK.with(|current| current.borrow_mut().replace(sink.clone()));
+    let _restore = RestoreSink(previous);
+    f()
+}
+
+fn render_record(record: &log::Record) -> String {
+    let mut line = format!("{} {}: {}", record.level(), record.target(), record.args());
+
+    struct Visitor<'a>(&'a mut String);
+
+    impl<'kvs> log::kv::VisitSource<'kvs> for Visitor<'_> {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            write!(self.0, " {}={:?}", key, value).map_err(|_| log::kv::Error::msg("write failed"))
+        }
+    }
+
+    record.key_values().visit(&mut Visitor(&mut line)).ok();
+    line
+}
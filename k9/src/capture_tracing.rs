@@ -0,0 +1,164 @@
+use crate::debug_tree::push_indent;
+use crate::Snap;
+use std::collections::HashMap;
+use std::fmt::{Debug, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Run the closure with a [tracing](https://docs.rs/tracing) subscriber that appends every
+/// event emitted on the current thread to `sink`.
+///
+/// Every event is rendered as a single line: level, target, message and fields.
+/// Entering a span adds a `name{fields}` line and indents everything
+/// recorded inside of it.
+///
+/// ```
+/// let logs = k9::Snap::new();
+/// k9::capture_tracing(&logs, || {
+///     let span = tracing::info_span!("request", method = "GET");
+///     let _guard = span.enter();
+///     tracing::info!(status = 200, "done");
+/// });
+///
+/// k9::snapshot!(
+///     logs.to_string(),
+///     r#"
+/// request{method="GET"}
+///     INFO rust_out: done status=200
+///
+/// "#
+/// );
+/// ```
+pub fn capture_tracing<F: FnOnce() -> T, T>(sink: &Snap, f: F) -> T {
+    let subscriber = CapturingSubscriber {
+        sink: sink.clone(),
+        next_id: AtomicU64::new(1),
+        spans: Mutex::new(HashMap::new()),
+        stack: Mutex::new(vec![]),
+    };
+    tracing::subscriber::with_default(subscriber, f)
+}
+
+struct SpanData {
+    name: &'static str,
+    fields: String,
+    /// Number of open handles to the span. Its data is removed once the last one is closed
+    refs: usize,
+}
+
+struct CapturingSubscriber {
+    sink: Snap,
+    next_id: AtomicU64,
+    spans: Mutex<HashMap<u64, SpanData>>,
+    /// Spans that are currently entered, innermost last
+    stack: Mutex<Vec<u64>>,
+}
+
+/// Renders fields as ` key=value`, pulling out the `message` field
+#[derive(Default)]
+struct FieldsVisitor {
+    message: Option<String>,
+    fields: String,
+}
+
+impl Visit for FieldsVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        } else {
+            write!(self.fields, " {}={:?}", field.name(), value).unwrap();
+        }
+    }
+}
+
+impl Subscriber for CapturingSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let mut visitor = FieldsVisitor::default();
+        attributes.record(&mut visitor);
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.spans.lock().unwrap().insert(
+            id,
+            SpanData {
+                name: attributes.metadata().name(),
+                fields: visitor.fields,
+                refs: 1,
+            },
+        );
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut visitor = FieldsVisitor::default();
+        values.record(&mut visitor);
+        if let Some(span) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            span.fields.push_str(&visitor.fields);
+        }
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = FieldsVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+
+        let mut line = String::new();
+        push_indent(&mut line, self.stack.lock().unwrap().len());
+        write!(line, "{} {}:", metadata.level(), metadata.target()).unwrap();
+        if let Some(message) = visitor.message {
+            write!(line, " {}", message).unwrap();
+        }
+        line.push_str(&visitor.fields);
+        self.sink.pushln(line);
+    }
+
+    fn enter(&self, span: &Id) {
+        let mut stack = self.stack.lock().unwrap();
+        if let Some(data) = self.spans.lock().unwrap().get(&span.into_u64()) {
+            let mut line = String::new();
+            push_indent(&mut line, stack.len());
+            line.push_str(data.name);
+            if !data.fields.is_empty() {
+                write!(line, "{{{}}}", data.fields.trim_start()).unwrap();
+            }
+            self.sink.pushln(line);
+        }
+        stack.push(span.into_u64());
+    }
+
+    fn exit(&self, span: &Id) {
+        let mut stack = self.stack.lock().unwrap();
+        if let Some(position) = stack.iter().rposition(|id| *id == span.into_u64()) {
+            stack.remove(position);
+        }
+    }
+
+    fn clone_span(&self, span: &Id) -> Id {
+        if let Some(data) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            data.refs += 1;
+        }
+        span.clone()
+    }
+
+    fn try_close(&self, span: Id) -> bool {
+        let mut spans = self.spans.lock().unwrap();
+        let closed = match spans.get_mut(&span.into_u64()) {
+            Some(data) => {
+                data.refs -= 1;
+                data.refs == 0
+            }
+            None => false,
+        };
+        if closed {
+            spans.remove(&span.into_u64());
+        }
+        closed
+    }
+}
@@ -1,6 +1,12 @@
 use colored::*;
 use lazy_static::lazy_static;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+/// Default for [max_message_size]
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 20_000;
 
 pub enum BuildSystem {
     /// https://buck.build/
@@ -20,6 +26,29 @@ impl BuildSystem {
     }
 }
 
+/// How [colored_diff](crate::string_diff::colored_diff) lays out the difference
+/// between two values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLayout {
+    /// Removed and added lines one after another, in a single column
+    Unified,
+    /// Left and right values in two columns next to each other. Falls back to
+    /// [DiffLayout::Unified] if the terminal is too narrow to fit both
+    SideBySide,
+}
+
+/// How [colored_diff](crate::string_diff::colored_diff) lines up the left and
+/// right values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffAlgorithm {
+    /// The smallest possible diff
+    Myers,
+    /// Lines up lines that occur only once on both sides first (e.g. function
+    /// signatures) and diffs the rest between them. Usually easier to read for
+    /// code-like text, where the smallest diff tends to match up unrelated `}` lines
+    Patience,
+}
+
 pub struct Config {
     /// Configurable so we can test all assertions in our own test suite without panicking.
     pub assertions_will_panic: AtomicBool,
@@ -31,6 +60,20 @@ pub struct Config {
     pub build_system: BuildSystem,
     /// Whether we should always enable colored output
     pub force_enable_colors: bool,
+    /// [DiffLayout] stored as `u8`
+    pub diff_layout: AtomicU8,
+    /// Number of unchanged lines printed around every change in a diff.
+    /// `usize::MAX` prints all of them
+    pub diff_context_lines: AtomicUsize,
+    /// [DiffAlgorithm] stored as `u8`
+    pub diff_algorithm: AtomicU8,
+    /// Print line numbers of both values next to the diff
+    pub diff_line_numbers: AtomicBool,
+    /// Failure messages longer than this (in characters) are truncated and the
+    /// full values are written to [artifacts_dir]. `usize::MAX` disables truncation
+    pub max_message_size: AtomicUsize,
+    /// `None` if the default [artifacts_dir] is used
+    pub artifacts_dir: RwLock<Option<PathBuf>>,
 }
 
 lazy_static! {
@@ -40,6 +83,14 @@ lazy_static! {
         update_mode: is_update_mode(),
         build_system: build_system(),
         force_enable_colors: should_force_enable_colors(),
+        diff_layout: AtomicU8::new(default_diff_layout() as u8),
+        diff_context_lines: AtomicUsize::new(default_diff_context_lines()),
+        diff_algorithm: AtomicU8::new(default_diff_algorithm() as u8),
+        diff_line_numbers: AtomicBool::new(
+            std::env::var("K9_DIFF_LINE_NUMBERS").as_deref() == Ok("1")
+        ),
+        max_message_size: AtomicUsize::new(default_max_message_size()),
+        artifacts_dir: RwLock::new(std::env::var_os("K9_ARTIFACTS_DIR").map(PathBuf::from)),
     };
 }
 
@@ -47,8 +98,47 @@ pub fn set_panic(v: bool) {
     CONFIG.assertions_will_panic.store(v, Ordering::Relaxed)
 }
 
+thread_local! {
+    /// Assertions nested inside of other assertions (e.g. an `assert_all!` predicate)
+    /// report their failures back to the outer assertion instead of panicking.
+    /// One list of failure messages for every level of nesting.
+    static NESTED_ASSERTION_FAILURES: RefCell<Vec<Vec<String>>> = const { RefCell::new(vec![]) };
+}
+
 pub fn should_panic() -> bool {
     CONFIG.assertions_will_panic.load(Ordering::Relaxed)
+        && NESTED_ASSERTION_FAILURES.with(|failures| failures.borrow().is_empty())
+}
+
+/// Run `f` with panicking disabled for all assertions invoked on the current thread.
+/// Returns failure messages of all assertions that failed while running it.
+pub(crate) fn nested_assertions<T>(f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    struct DepthGuard;
+
+    impl Drop for DepthGuard {
+        fn drop(&mut self) {
+            NESTED_ASSERTION_FAILURES.with(|failures| failures.borrow_mut().pop());
+        }
+    }
+
+    NESTED_ASSERTION_FAILURES.with(|failures| failures.borrow_mut().push(vec![]));
+    let guard = DepthGuard;
+    let result = f();
+    let failures = NESTED_ASSERTION_FAILURES
+        .with(|failures| failures.borrow_mut().last_mut().map(std::mem::take))
+        .unwrap_or_default();
+    drop(guard);
+    (result, failures)
+}
+
+/// Record a failed assertion if it's nested inside of another assertion.
+/// The message is only built when there's an outer assertion to report it to.
+pub(crate) fn record_nested_failure(message: impl FnOnce() -> String) {
+    NESTED_ASSERTION_FAILURES.with(|failures| {
+        if let Some(current) = failures.borrow_mut().last_mut() {
+            current.push(message());
+        }
+    });
 }
 
 pub fn set_terminal_with_override(width: usize) {
@@ -61,6 +151,108 @@ pub fn terminal_width_override() -> usize {
     CONFIG.terminal_width_override.load(Ordering::Relaxed)
 }
 
+pub fn set_diff_layout(layout: DiffLayout) {
+    CONFIG.diff_layout.store(layout as u8, Ordering::Relaxed)
+}
+
+pub fn diff_layout() -> DiffLayout {
+    match CONFIG.diff_layout.load(Ordering::Relaxed) {
+        x if x == DiffLayout::SideBySide as u8 => DiffLayout::SideBySide,
+        _ => DiffLayout::Unified,
+    }
+}
+
+fn default_diff_layout() -> DiffLayout {
+    match std::env::var("K9_DIFF_LAYOUT").as_deref() {
+        Ok("side-by-side") => DiffLayout::SideBySide,
+        _ => DiffLayout::Unified,
+    }
+}
+
+pub fn set_diff_context_lines(lines: usize) {
+    CONFIG.diff_context_lines.store(lines, Ordering::Relaxed)
+}
+
+pub fn diff_context_lines() -> usize {
+    CONFIG.diff_context_lines.load(Ordering::Relaxed)
+}
+
+fn default_diff_context_lines() -> usize {
+    // Unchanged lines are collapsed unless the full diff is explicitly requested
+    match std::env::var("K9_FULL_DIFF").as_deref() {
+        Ok("1") => usize::MAX,
+        _ => 3,
+    }
+}
+
+pub fn set_diff_algorithm(algorithm: DiffAlgorithm) {
+    CONFIG
+        .diff_algorithm
+        .store(algorithm as u8, Ordering::Relaxed)
+}
+
+pub fn diff_algorithm() -> DiffAlgorithm {
+    match CONFIG.diff_algorithm.load(Ordering::Relaxed) {
+        x if x == DiffAlgorithm::Patience as u8 => DiffAlgorithm::Patience,
+        _ => DiffAlgorithm::Myers,
+    }
+}
+
+fn default_diff_algorithm() -> DiffAlgorithm {
+    match std::env::var("K9_DIFF_ALGORITHM").as_deref() {
+        Ok("patience") => DiffAlgorithm::Patience,
+        _ => DiffAlgorithm::Myers,
+    }
+}
+
+pub fn set_diff_line_numbers(v: bool) {
+    CONFIG.diff_line_numbers.store(v, Ordering::Relaxed)
+}
+
+pub fn diff_line_numbers() -> bool {
+    CONFIG.diff_line_numbers.load(Ordering::Relaxed)
+}
+
+pub fn set_max_message_size(size: usize) {
+    CONFIG.max_message_size.store(size, Ordering::Relaxed)
+}
+
+pub fn max_message_size() -> usize {
+    CONFIG.max_message_size.load(Ordering::Relaxed)
+}
+
+fn default_max_message_size() -> usize {
+    std::env::var("K9_MAX_MESSAGE_SIZE")
+        .ok()
+        .and_then(|size| size.parse().ok())
+        .unwrap_or(DEFAULT_MAX_MESSAGE_SIZE)
+}
+
+pub fn set_artifacts_dir(dir: impl Into<PathBuf>) {
+    *CONFIG.artifacts_dir.write().expect("poisoned lock") = Some(dir.into());
+}
+
+/// Directory that values too large to print in failure messages are written to.
+/// Every test gets its own subdirectory in it
+pub fn artifacts_dir() -> PathBuf {
+    let dir = CONFIG.artifacts_dir.read().expect("poisoned lock").clone();
+    dir.unwrap_or_else(default_artifacts_dir)
+}
+
+fn default_artifacts_dir() -> PathBuf {
+    // Cargo builds test binaries into `<target dir>/<profile>/deps`
+    let target_dir = std::env::current_exe().ok().and_then(|exe| {
+        let deps = exe.parent()?;
+        if deps.file_name()? != "deps" {
+            return None;
+        }
+        Some(deps.parent()?.parent()?.to_path_buf())
+    });
+    target_dir
+        .unwrap_or_else(|| crate::paths::get_project_root_path().join("target"))
+        .join("k9-artifacts")
+}
+
 fn build_system() -> BuildSystem {
     if std::env::var("BUCK_BUILD_ID").is_ok() {
         BuildSystem::Buck
@@ -73,7 +265,7 @@ fn build_system() -> BuildSystem {
 
 fn is_update_mode() -> bool {
     // If runtime ENV variable is set, it takes precedence
-    std::env::var("K9_UPDATE_SNAPSHOTS").map_or(false, |_| true)
+    std::env::var("K9_UPDATE_SNAPSHOTS").is_ok()
 }
 
 fn should_force_enable_colors() -> bool {
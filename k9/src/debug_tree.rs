@@ -0,0 +1,419 @@
+/// Parsed representation of a value's pretty `{:#?}` output.
+///
+/// `Debug` output is the only thing we know about arbitrary values, so
+/// assertions that need to look inside of a value (e.g. pick a single field
+/// of a struct) parse it back into a tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Node {
+    /// `Name { field: value }`
+    Struct {
+        name: String,
+        fields: Vec<(String, Node)>,
+    },
+    /// `Name(value)`, or `(value, value)` for plain tuples
+    Tuple { name: String, items: Vec<Node> },
+    /// `[value, value]`
+    Seq(Vec<Node>),
+    /// `{value, value}`
+    Set(Vec<Node>),
+    /// `{key: value}`
+    Map(Vec<(Node, Node)>),
+    /// Anything that doesn't have nested values. e.g. `1`, `"hello"`, `None`
+    Atom(String),
+}
+
+impl Node {
+    /// Parse output of `{:#?}`. If it can't be understood, the whole
+    /// string is returned as a single [Node::Atom]
+    pub fn parse(s: &str) -> Self {
+        let mut parser = Parser {
+            chars: s.chars().collect(),
+            pos: 0,
+        };
+
+        parser
+            .parse_node()
+            .filter(|_| {
+                parser.skip_whitespace();
+                parser.pos == parser.chars.len()
+            })
+            .unwrap_or_else(|| Node::Atom(s.trim().to_string()))
+    }
+
+    /// Render the node the same way `{:#?}` would.
+    pub fn render(&self) -> String {
+        let mut result = String::new();
+        self.render_into(&mut result, 0);
+        result
+    }
+
+    /// Render the node on a single line, the same way `{:?}` would.
+    pub fn render_compact(&self) -> String {
+        let join = |items: &mut dyn Iterator<Item = String>| items.collect::<Vec<_>>().join(", ");
+        match self {
+            Node::Atom(s) => s.clone(),
+            Node::Struct { name, fields } if fields.is_empty() => name.clone(),
+            Node::Struct { name, fields } => format!(
+                "{} {{ {} }}",
+                name,
+                join(&mut fields.iter().map(|(field, value)| format!(
+                    "{}: {}",
+                    field,
+                    value.render_compact()
+                )))
+            ),
+            Node::Tuple { name, items } if items.is_empty() && !name.is_empty() => name.clone(),
+            Node::Tuple { name, items } => format!(
+                "{}({})",
+                name,
+                join(&mut items.iter().map(Node::render_compact))
+            ),
+            Node::Seq(items) => format!("[{}]", join(&mut items.iter().map(Node::render_compact))),
+            Node::Set(items) => {
+                format!("{{{}}}", join(&mut items.iter().map(Node::render_compact)))
+            }
+            Node::Map(entries) => format!(
+                "{{{}}}",
+                join(&mut entries.iter().map(|(key, value)| format!(
+                    "{}: {}",
+                    key.render_compact(),
+                    value.render_compact()
+                )))
+            ),
+        }
+    }
+
+    pub fn render_into(&self, out: &mut String, indent: usize) {
+        match self {
+            Node::Atom(s) => out.push_str(s),
+            Node::Struct { name, fields } => {
+                out.push_str(name);
+                if !fields.is_empty() {
+                    out.push_str(" {\n");
+                    for (field, value) in fields {
+                        push_indent(out, indent + 1);
+                        out.push_str(field);
+                        out.push_str(": ");
+                        value.render_into(out, indent + 1);
+                        out.push_str(",\n");
+                    }
+                    push_indent(out, indent);
+                    out.push('}');
+                }
+            }
+            Node::Tuple { name, items } => {
+                out.push_str(name);
+                if items.is_empty() {
+                    if name.is_empty() {
+                        out.push_str("()");
+                    }
+                } else {
+                    render_items(out, indent, "(", ")", items);
+                }
+            }
+            Node::Seq(items) => render_items(out, indent, "[", "]", items),
+            Node::Set(items) => render_items(out, indent, "{", "}", items),
+            Node::Map(entries) => {
+                if entries.is_empty() {
+                    out.push_str("{}");
+                } else {
+                    out.push_str("{\n");
+                    for (key, value) in entries {
+                        push_indent(out, indent + 1);
+                        key.render_into(out, indent + 1);
+                        out.push_str(": ");
+                        value.render_into(out, indent + 1);
+                        out.push_str(",\n");
+                    }
+                    push_indent(out, indent);
+                    out.push('}');
+                }
+            }
+        }
+    }
+}
+
+fn render_items(out: &mut String, indent: usize, open: &str, close: &str, items: &[Node]) {
+    out.push_str(open);
+    if !items.is_empty() {
+        out.push('\n');
+        for item in items {
+            push_indent(out, indent + 1);
+            item.render_into(out, indent + 1);
+            out.push_str(",\n");
+        }
+        push_indent(out, indent);
+    }
+    out.push_str(close);
+}
+
+pub fn push_indent(out: &mut String, indent: usize) {
+    out.push_str(&"    ".repeat(indent));
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// `: ` separates keys from values. A colon that isn't followed by
+    /// whitespace is part of an atom (e.g. a path `std::fmt` or a time `10:00`)
+    fn at_key_separator(&self) -> bool {
+        self.peek() == Some(':') && matches!(self.peek_at(1), Some(c) if c.is_whitespace())
+    }
+
+    fn parse_node(&mut self) -> Option<Node> {
+        self.skip_whitespace();
+        match self.peek()? {
+            '[' => {
+                self.pos += 1;
+                Some(Node::Seq(self.parse_items(']')?))
+            }
+            '(' => {
+                self.pos += 1;
+                Some(Node::Tuple {
+                    name: String::new(),
+                    items: self.parse_items(')')?,
+                })
+            }
+            '{' => {
+                self.pos += 1;
+                self.parse_braces()
+            }
+            '"' | '\'' => Some(Node::Atom(self.parse_quoted()?)),
+            _ => {
+                let word = self.parse_word();
+                if word.is_empty() {
+                    return None;
+                }
+                self.skip_whitespace();
+                match self.peek() {
+                    Some('(') => {
+                        self.pos += 1;
+                        Some(Node::Tuple {
+                            name: word,
+                            items: self.parse_items(')')?,
+                        })
+                    }
+                    Some('{') => {
+                        self.pos += 1;
+                        Some(Node::Struct {
+                            name: word,
+                            fields: self.parse_fields()?,
+                        })
+                    }
+                    _ => Some(Node::Atom(word)),
+                }
+            }
+        }
+    }
+
+    fn parse_word(&mut self) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if matches!(c, ',' | '(' | ')' | '[' | ']' | '{' | '}') || self.at_key_separator() {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    fn parse_quoted(&mut self) -> Option<String> {
+        let quote = self.peek()?;
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.peek()? {
+                '\\' => self.pos += 2,
+                c if c == quote => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => self.pos += 1,
+            }
+        }
+        Some(self.chars[start..self.pos].iter().collect())
+    }
+
+    /// Consume a `,` between items. Returns `true` if the closing bracket was reached
+    fn parse_separator(&mut self, close: char) -> Option<bool> {
+        if self.eat(',') {
+            Some(self.eat(close))
+        } else if self.eat(close) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    fn parse_items(&mut self, close: char) -> Option<Vec<Node>> {
+        let mut items = vec![];
+        if self.eat(close) {
+            return Some(items);
+        }
+        loop {
+            items.push(self.parse_node()?);
+            if self.parse_separator(close)? {
+                return Some(items);
+            }
+        }
+    }
+
+    fn parse_fields(&mut self) -> Option<Vec<(String, Node)>> {
+        let mut fields = vec![];
+        if self.eat('}') {
+            return Some(fields);
+        }
+        loop {
+            self.skip_whitespace();
+            // non exhaustive structs end with `..`
+            if self.peek() == Some('.') && self.peek_at(1) == Some('.') {
+                self.pos += 2;
+                return if self.eat('}') { Some(fields) } else { None };
+            }
+            let name = self.parse_word();
+            if name.is_empty() || !self.at_key_separator() {
+                return None;
+            }
+            self.pos += 1;
+            fields.push((name, self.parse_node()?));
+            if self.parse_separator('}')? {
+                return Some(fields);
+            }
+        }
+    }
+
+    fn parse_braces(&mut self) -> Option<Node> {
+        if self.eat('}') {
+            return Some(Node::Map(vec![]));
+        }
+        let first = self.parse_node()?;
+        self.skip_whitespace();
+        if self.at_key_separator() {
+            let mut entries = vec![];
+            let mut key = first;
+            loop {
+                self.skip_whitespace();
+                if !self.at_key_separator() {
+                    return None;
+                }
+                self.pos += 1;
+                entries.push((key, self.parse_node()?));
+                if self.parse_separator('}')? {
+                    return Some(Node::Map(entries));
+                }
+                key = self.parse_node()?;
+            }
+        } else {
+            let mut items = vec![first];
+            while !self.parse_separator('}')? {
+                items.push(self.parse_node()?);
+            }
+            Some(Node::Set(items))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Address {
+        zip: u32,
+        street: String,
+    }
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct User {
+        name: &'static str,
+        tags: Vec<&'static str>,
+        address: Option<Address>,
+        scores: BTreeMap<&'static str, (u8, f32)>,
+        ids: BTreeSet<i64>,
+        unit: (),
+        empty: Vec<u8>,
+    }
+
+    #[test]
+    fn roundtrip() {
+        let user = User {
+            name: "Kelly, \"K\" {[(",
+            tags: vec!["a", "b"],
+            address: Some(Address {
+                zip: 12345,
+                street: "Main st".to_string(),
+            }),
+            scores: vec![("math", (5, 4.5))].into_iter().collect(),
+            ids: vec![-1, 2].into_iter().collect(),
+            unit: (),
+            empty: vec![],
+        };
+
+        let debug = format!("{:#?}", user);
+        let node = Node::parse(&debug);
+        k9_stable::assert_equal!(node.render(), debug);
+        assert!(matches!(node, Node::Struct { .. }));
+    }
+
+    #[test]
+    fn render_compact() {
+        let value = (
+            Some(Address {
+                zip: 12345,
+                street: "Main st".to_string(),
+            }),
+            vec![("a", ())],
+            BTreeSet::<u8>::new(),
+        );
+        k9_stable::assert_equal!(
+            Node::parse(&format!("{:#?}", value)).render_compact(),
+            format!("{:?}", value)
+        );
+    }
+
+    #[test]
+    fn unparseable() {
+        k9_stable::assert_equal!(
+            Node::parse(" hello (world "),
+            Node::Atom("hello (world".to_string())
+        );
+        k9_stable::assert_equal!(
+            Node::parse("10:00:00 UTC"),
+            Node::Atom("10:00:00 UTC".to_string())
+        );
+    }
+}
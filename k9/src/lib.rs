@@ -4,20 +4,44 @@ see <https://github.com/aaronabramov/k9> for high level description of the libra
 
 #![cfg_attr(feature = "custom_comparison_formatters", feature(specialization))]
 
+pub mod alloc;
 pub mod assertions;
 pub mod config;
 pub mod snapshot;
 pub mod string_diff;
 
+mod artifacts;
+#[cfg(unix)]
+mod capture;
+#[cfg(feature = "log")]
+mod capture_log;
+#[cfg(feature = "tracing")]
+mod capture_tracing;
+mod debug_tree;
 mod multiline_string;
 mod paths;
+mod sequence_diff;
 mod snap;
+mod structural_diff;
 mod types;
 mod utils;
 
+#[cfg(unix)]
+pub use capture::{capture_output, CapturedOutput};
+#[cfg(feature = "log")]
+pub use capture_log::capture_log;
+#[cfg(feature = "tracing")]
+pub use capture_tracing::capture_tracing;
 pub use multiline_string::MultilineString;
 pub use snap::Snap;
 
+/// Matchers that can be used in [assert_matches_shape!](crate::assert_matches_shape) templates
+pub mod shape {
+    #[cfg(feature = "regex")]
+    pub use crate::assertions::matches_shape::matches_regex;
+    pub use crate::assertions::matches_shape::{any, any_number, any_string, ShapeMatcher};
+}
+
 // re-export things so macros have access to them
 pub mod __macros__ {
     pub use colored;
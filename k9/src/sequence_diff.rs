@@ -0,0 +1,449 @@
+use crate::config::{diff_algorithm, DiffAlgorithm};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Range;
+
+/// Rough number of element comparisons a single [Myers](DiffAlgorithm::Myers)
+/// pass is allowed to make. Above it the inputs are only aligned on lines that
+/// are unique on both sides and the rest is reported as replaced, which
+/// is less precise but finishes quickly on huge values
+const MAX_COST: usize = 20_000_000;
+
+/// Myers is always allowed to look for at least this many edits,
+/// no matter how long the inputs are
+const MIN_EDIT_DISTANCE: usize = 256;
+
+/// A fragment of a computed diff
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edit<T> {
+    /// An element that only exists in the left input
+    Left(T),
+    /// Elements that exist in both inputs
+    Both(T, T),
+    /// An element that only exists in the right input
+    Right(T),
+}
+
+/// Diff two slices using the configured [DiffAlgorithm]
+pub fn slice<'a, T: Hash + Eq>(left: &'a [T], right: &'a [T]) -> Vec<Edit<&'a T>> {
+    slice_with(left, right, diff_algorithm())
+}
+
+/// Same as [slice], but using the given algorithm
+pub fn slice_with<'a, T: Hash + Eq>(
+    left: &'a [T],
+    right: &'a [T],
+    algorithm: DiffAlgorithm,
+) -> Vec<Edit<&'a T>> {
+    diff_with(left, right, algorithm)
+        .into_iter()
+        .map(|edit| match edit {
+            Edit::Left(l) => Edit::Left(&left[l]),
+            Edit::Both(l, r) => Edit::Both(&left[l], &right[r]),
+            Edit::Right(r) => Edit::Right(&right[r]),
+        })
+        .collect()
+}
+
+/// Diff the lines of two strings. A trailing `\n` counts as an empty last line.
+/// Unlike [str::lines] this keeps `\r` at the end of lines, so `\r\n` and
+/// `\n` line endings are different
+pub fn lines<'a>(left: &'a str, right: &'a str, algorithm: DiffAlgorithm) -> Vec<Edit<&'a str>> {
+    let split = |s: &'a str| {
+        if s.is_empty() {
+            vec![]
+        } else {
+            s.split('\n').collect::<Vec<_>>()
+        }
+    };
+    let (left, right) = (split(left), split(right));
+    slice_with(&left, &right, algorithm)
+        .into_iter()
+        .map(|edit| match edit {
+            Edit::Left(l) => Edit::Left(*l),
+            Edit::Both(l, r) => Edit::Both(*l, *r),
+            Edit::Right(r) => Edit::Right(*r),
+        })
+        .collect()
+}
+
+/// Diff two slices, returning indices of elements. Within every changed
+/// region all removed elements come before the added ones
+pub(crate) fn diff_with<T: Hash + Eq>(
+    left: &[T],
+    right: &[T],
+    algorithm: DiffAlgorithm,
+) -> Vec<Edit<usize>> {
+    let mut differ = Differ {
+        left,
+        right,
+        algorithm,
+        edits: vec![],
+    };
+    differ.diff(0..left.len(), 0..right.len());
+    group_changes(differ.edits, left, right)
+}
+
+struct Differ<'a, T> {
+    left: &'a [T],
+    right: &'a [T],
+    algorithm: DiffAlgorithm,
+    edits: Vec<Edit<usize>>,
+}
+
+impl<'a, T: Hash + Eq> Differ<'a, T> {
+    fn diff(&mut self, mut l: Range<usize>, mut r: Range<usize>) {
+        while !l.is_empty() && !r.is_empty() && self.left[l.start] == self.right[r.start] {
+            self.edits.push(Edit::Both(l.start, r.start));
+            l.start += 1;
+            r.start += 1;
+        }
+        let mut suffix = 0;
+        while !l.is_empty() && !r.is_empty() && self.left[l.end - 1] == self.right[r.end - 1] {
+            l.end -= 1;
+            r.end -= 1;
+            suffix += 1;
+        }
+
+        if l.is_empty() || r.is_empty() {
+            self.edits.extend(l.clone().map(Edit::Left));
+            self.edits.extend(r.clone().map(Edit::Right));
+        } else if self.algorithm == DiffAlgorithm::Patience {
+            if !self.diff_around_unique(l.clone(), r.clone()) {
+                self.diff_myers(l.clone(), r.clone());
+            }
+        } else {
+            self.diff_myers(l.clone(), r.clone());
+        }
+
+        self.edits
+            .extend((0..suffix).map(|i| Edit::Both(l.end + i, r.end + i)));
+    }
+
+    fn diff_myers(&mut self, l: Range<usize>, r: Range<usize>) {
+        let max_edits = (MAX_COST / (l.len() + r.len())).max(MIN_EDIT_DISTANCE);
+        match middle_snake(&self.left[l.clone()], &self.right[r.clone()], max_edits) {
+            Some(snake) => {
+                let (x, y) = (l.start + snake.start.0, r.start + snake.start.1);
+                let (u, v) = (l.start + snake.end.0, r.start + snake.end.1);
+                self.diff(l.start..x, r.start..y);
+                self.edits
+                    .extend((0..u - x).map(|i| Edit::Both(x + i, y + i)));
+                self.diff(u..l.end, v..r.end);
+            }
+            None => {
+                if !self.diff_around_unique(l.clone(), r.clone()) {
+                    self.edits.extend(l.map(Edit::Left));
+                    self.edits.extend(r.map(Edit::Right));
+                }
+            }
+        }
+    }
+
+    /// Patience diff: match up elements that occur exactly once on both sides
+    /// and diff the gaps between them separately. Returns `false` if there are
+    /// no such elements
+    fn diff_around_unique(&mut self, l: Range<usize>, r: Range<usize>) -> bool {
+        let anchors = unique_anchors(&self.left[l.clone()], &self.right[r.clone()]);
+        if anchors.is_empty() {
+            return false;
+        }
+
+        let (mut x, mut y) = (l.start, r.start);
+        for (i, j) in anchors {
+            let (i, j) = (l.start + i, r.start + j);
+            self.diff(x..i, y..j);
+            self.edits.push(Edit::Both(i, j));
+            x = i + 1;
+            y = j + 1;
+        }
+        self.diff(x..l.end, y..r.end);
+        true
+    }
+}
+
+/// Common subsequence found halfway through the shortest edit script
+struct Snake {
+    start: (usize, usize),
+    end: (usize, usize),
+}
+
+/// Linear space variant of Myers' algorithm from "An O(ND) Difference Algorithm
+/// and Its Variations". Searches from both ends at once until the paths meet.
+/// Returns `None` if the inputs differ by more than `max_edits` elements
+fn middle_snake<T: Eq>(a: &[T], b: &[T], max_edits: usize) -> Option<Snake> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (n + m + 1) / 2;
+    let delta = n - m;
+    let offset = max + 1;
+    // Furthest reaching x on every diagonal, counted from the start for
+    // `forward` and from the end for `backward`
+    let mut forward = vec![0isize; 2 * max as usize + 3];
+    let mut backward = vec![0isize; 2 * max as usize + 3];
+
+    for d in 0..=max.min(max_edits as isize) {
+        for k in (-d..=d).step_by(2) {
+            let i = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && forward[i - 1] < forward[i + 1]) {
+                forward[i + 1]
+            } else {
+                forward[i - 1] + 1
+            };
+            let start = (x, x - k);
+            while x < n && x - k < m && a[x as usize] == b[(x - k) as usize] {
+                x += 1;
+            }
+            forward[i] = x;
+
+            let reverse_k = delta - k;
+            if delta % 2 != 0
+                && reverse_k.abs() < d
+                && x + backward[(reverse_k + offset) as usize] >= n
+            {
+                return Some(Snake {
+                    start: (start.0 as usize, start.1 as usize),
+                    end: (x as usize, (x - k) as usize),
+                });
+            }
+        }
+
+        for k in (-d..=d).step_by(2) {
+            let i = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && backward[i - 1] < backward[i + 1]) {
+                backward[i + 1]
+            } else {
+                backward[i - 1] + 1
+            };
+            let end = (x, x - k);
+            while x < n && x - k < m && a[(n - x - 1) as usize] == b[(m - x + k - 1) as usize] {
+                x += 1;
+            }
+            backward[i] = x;
+
+            let forward_k = delta - k;
+            if delta % 2 == 0
+                && forward_k.abs() <= d
+                && x + forward[(forward_k + offset) as usize] >= n
+            {
+                return Some(Snake {
+                    start: ((n - x) as usize, (m - x + k) as usize),
+                    end: ((n - end.0) as usize, (m - end.1) as usize),
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Pairs of indices of elements that occur exactly once in both `a` and `b`,
+/// reduced to the longest chain that is increasing on both sides
+fn unique_anchors<T: Hash + Eq>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    // occurrences in `a`, occurrences in `b`, index in `a`, index in `b`
+    let mut counts: HashMap<&T, (usize, usize, usize, usize)> = HashMap::new();
+    for (i, item) in a.iter().enumerate() {
+        let entry = counts.entry(item).or_default();
+        entry.0 += 1;
+        entry.2 = i;
+    }
+    for (j, item) in b.iter().enumerate() {
+        if let Some(entry) = counts.get_mut(item) {
+            entry.1 += 1;
+            entry.3 = j;
+        }
+    }
+    let mut pairs = counts
+        .into_values()
+        .filter(|&(in_a, in_b, _, _)| in_a == 1 && in_b == 1)
+        .map(|(_, _, i, j)| (i, j))
+        .collect::<Vec<_>>();
+    pairs.sort_unstable();
+
+    // Patience sorting: `piles[p]` is the pair ending the best chain of
+    // length `p + 1`, `previous` links every pair to the one before it
+    let mut piles: Vec<usize> = vec![];
+    let mut previous = vec![None; pairs.len()];
+    for (index, &(_, j)) in pairs.iter().enumerate() {
+        let pile = piles.partition_point(|&top| pairs[top].1 < j);
+        if pile > 0 {
+            previous[index] = Some(piles[pile - 1]);
+        }
+        if pile == piles.len() {
+            piles.push(index);
+        } else {
+            piles[pile] = index;
+        }
+    }
+
+    let mut chain = vec![];
+    let mut current = piles.last().copied();
+    while let Some(index) = current {
+        chain.push(pairs[index]);
+        current = previous[index];
+    }
+    chain.reverse();
+    chain
+}
+
+/// Reorder every run of changes so removed elements come first, the way they
+/// are printed.
+///
+/// An unchanged element right after a run of changes is also matched with the
+/// first equal element of that run if there is one. Both versions are equally
+/// short, but this one keeps a changed line next to what replaced it instead of
+/// e.g. matching the `),` of the next struct field
+fn group_changes<T: Eq>(edits: Vec<Edit<usize>>, left: &[T], right: &[T]) -> Vec<Edit<usize>> {
+    let mut result = Vec::with_capacity(edits.len());
+    let mut removed: Vec<usize> = vec![];
+    let mut added: Vec<usize> = vec![];
+    for edit in edits {
+        match edit {
+            Edit::Left(l) => removed.push(l),
+            Edit::Right(r) => added.push(r),
+            Edit::Both(l, r) => {
+                let earliest_l = removed.iter().position(|&i| left[i] == left[l]);
+                let earliest_r = added.iter().position(|&j| right[j] == right[r]);
+                let split_l = earliest_l.unwrap_or(removed.len());
+                let split_r = earliest_r.unwrap_or(added.len());
+
+                result.extend(removed[..split_l].iter().copied().map(Edit::Left));
+                result.extend(added[..split_r].iter().copied().map(Edit::Right));
+                result.push(Edit::Both(
+                    earliest_l.map_or(l, |i| removed[i]),
+                    earliest_r.map_or(r, |j| added[j]),
+                ));
+
+                // Whatever comes after the new match becomes part of the next run
+                removed = match earliest_l {
+                    Some(i) => removed[i + 1..].iter().copied().chain(Some(l)).collect(),
+                    None => vec![],
+                };
+                added = match earliest_r {
+                    Some(j) => added[j + 1..].iter().copied().chain(Some(r)).collect(),
+                    None => vec![],
+                };
+            }
+        }
+    }
+    result.extend(removed.into_iter().map(Edit::Left));
+    result.extend(added.into_iter().map(Edit::Right));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    fn render(left: &[&str], right: &[&str], algorithm: DiffAlgorithm) -> Vec<String> {
+        diff_with(left, right, algorithm)
+            .into_iter()
+            .map(|edit| match edit {
+                Edit::Left(l) => format!("-{}", left[l]),
+                Edit::Both(l, _) => format!(" {}", left[l]),
+                Edit::Right(r) => format!("+{}", right[r]),
+            })
+            .collect()
+    }
+
+    fn lcs_length(a: &[u8], b: &[u8]) -> usize {
+        let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for i in 0..a.len() {
+            for j in 0..b.len() {
+                table[i + 1][j + 1] = if a[i] == b[j] {
+                    table[i][j] + 1
+                } else {
+                    table[i][j + 1].max(table[i + 1][j])
+                };
+            }
+        }
+        table[a.len()][b.len()]
+    }
+
+    #[test]
+    fn myers_is_minimal() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        for _ in 0..500 {
+            let mut random = |len: usize| {
+                (0..rng.gen_range(0, len))
+                    .map(|_| rng.gen_range(b'a', b'e'))
+                    .collect::<Vec<_>>()
+            };
+            let (a, b) = (random(20), random(20));
+            let edits = diff_with(&a, &b, DiffAlgorithm::Myers);
+
+            let (mut i, mut j) = (0, 0);
+            let mut both = 0;
+            for edit in &edits {
+                match *edit {
+                    Edit::Left(l) => {
+                        assert_eq!(l, i);
+                        i += 1;
+                    }
+                    Edit::Right(r) => {
+                        assert_eq!(r, j);
+                        j += 1;
+                    }
+                    Edit::Both(l, r) => {
+                        assert_eq!((l, r), (i, j));
+                        assert_eq!(a[l], b[r]);
+                        i += 1;
+                        j += 1;
+                        both += 1;
+                    }
+                }
+            }
+            assert_eq!((i, j), (a.len(), b.len()));
+            assert_eq!(both, lcs_length(&a, &b), "{:?} {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn patience_aligns_unique_lines() {
+        let left = ["}", "}", "}", "fn run() {", "start();"];
+        let right = ["fn run() {", "start();", "}", "}", "}"];
+
+        // the shortest diff keeps the closing braces in place
+        k9_stable::assert_equal!(
+            render(&left, &right, DiffAlgorithm::Myers),
+            vec![
+                "+fn run() {",
+                "+start();",
+                " }",
+                " }",
+                " }",
+                "-fn run() {",
+                "-start();"
+            ]
+        );
+        // while patience keeps the function, which is the only unique thing here
+        k9_stable::assert_equal!(
+            render(&left, &right, DiffAlgorithm::Patience),
+            vec![
+                "-}",
+                "-}",
+                "-}",
+                " fn run() {",
+                " start();",
+                "+}",
+                "+}",
+                "+}"
+            ]
+        );
+    }
+
+    #[test]
+    fn large_inputs_fall_back_to_coarse_diff() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let left = (0..100_000).map(|i| i % 1000).collect::<Vec<u32>>();
+        let right = left
+            .iter()
+            .map(|&x| if rng.gen_range(0, 3) == 0 { x + 1 } else { x })
+            .collect::<Vec<_>>();
+
+        let edits = diff_with(&left, &right, DiffAlgorithm::Myers);
+        let removed = edits.iter().filter(|e| matches!(e, Edit::Left(_))).count();
+        let added = edits.iter().filter(|e| matches!(e, Edit::Right(_))).count();
+        assert_eq!(edits.len() - removed, right.len());
+        assert_eq!(edits.len() - added, left.len());
+    }
+}
@@ -6,6 +6,8 @@ use std::sync::{Arc, Mutex};
 /// It makes passing it to different parts of tests easier when performance is not important.
 /// Useful for accumulating output from a system under test and later using it with
 /// [assert_matches_inline_snapshot](crate::assert_matches_inline_snapshot).
+/// This is also the sink that `k9::capture_log` and `k9::capture_tracing` write
+/// captured records into (available with `log` and `tracing` features).
 ///
 /// ```rust
 /// let snap = k9::Snap::new();
@@ -64,7 +64,7 @@ pub fn find_snapshot_literal_range<S: Into<String>>(
     })?;
 
     if literal_exists {
-        let literal = tt.into_iter().last();
+        let literal = last_argument_token(tt);
 
         if let Some(TokenTree::Literal(literal)) = literal {
             Ok(Range {
@@ -101,7 +101,7 @@ Given macro call:
             )
         }
     } else {
-        let last = tt.into_iter().last().expect("must have last tokentree");
+        let last = last_argument_token(tt).expect("must have last tokentree");
         let span = last.span();
 
         Ok(Range {
@@ -117,6 +117,17 @@ Given macro call:
     }
 }
 
+/// Last token of the macro call arguments, skipping a trailing comma
+fn last_argument_token(tt: TokenStream) -> Option<TokenTree> {
+    let mut tokens = tt.into_iter().collect::<Vec<_>>();
+    if let Some(TokenTree::Punct(punct)) = tokens.last() {
+        if punct.as_char() == ',' {
+            tokens.pop();
+        }
+    }
+    tokens.pop()
+}
+
 /// Convert proc_macro2 Span struct to local Range struct, which indexes
 /// for Lines and Columns starting from 1 and not 0
 fn syn_span_to_range(span: Span) -> Range {
@@ -142,6 +153,7 @@ fn main() {                                         // 2
     random_macro!(hello);                           // 4 
     hello_macro!(stuff, "literal");                 // 5
     wrong_macro!(stuff, not_a_literal);             // 6
+    comma_macro!(stuff, "literal",);                // 7
 }
 "##;
     #[test]
@@ -167,6 +179,20 @@ fn main() {                                         // 2
         Ok(())
     }
 
+    #[test]
+    fn trailing_comma() -> Result<()> {
+        let range = find_snapshot_literal_range(SOURCE, "comma_macro", 7, true)?;
+        k9_stable::snapshot!(
+            format!("{:?}", range),
+            r##"Range { start: LineColumn { line: 7, column: 25 }, end: LineColumn { line: 7, column: 34 } }"##
+        );
+
+        let range = find_snapshot_literal_range(SOURCE, "comma_macro", 7, false)?;
+        k9_stable::assert_equal!(&range.start, &range.end);
+        k9_stable::assert_equal!(range.start.column, 34);
+        Ok(())
+    }
+
     #[test]
     fn not_a_literal_error() {
         let err = find_snapshot_literal_range(SOURCE, "wrong_macro", 6, true).unwrap_err();
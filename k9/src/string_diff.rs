@@ -1,28 +1,578 @@
-use colored::*;
-use diff::{lines, Result};
-use std::fmt::Write;
+//! Line by line difference between two strings. [Diff] describes it and
+//! the `render_*` functions print it in different formats
+
+use crate::config::{self, DiffAlgorithm};
+use crate::sequence_diff::{self, Edit};
+use std::ops::Range;
+
+mod html;
+mod patch;
+mod plain;
+mod terminal;
+
+pub use html::render_html;
+pub use patch::render_unified_patch;
+pub use plain::render_plain;
+pub use terminal::render_colored;
+
+/// Lines with more tokens than this are highlighted as a whole, since comparing
+/// them token by token would take too long
+const MAX_HIGHLIGHTED_TOKENS: usize = 1000;
+
+/// Unchanged lines are only left out of hunks if there are at least this many
+/// of them, otherwise the separator wouldn't save much space
+const MIN_COLLAPSED_LINES: usize = 4;
+
+/// Byte ranges within a line
+type Ranges = Vec<Range<usize>>;
+
+/// A line with the numbers of the next left and right lines at the point it starts,
+/// which are where a hunk starting with it starts
+type NumberedLine = (DiffLine, (usize, usize));
+
+/// Line numbers of the first line of both values. Used when values are parts
+/// of a bigger file, so the line numbers printed next to the diff point to
+/// the right lines in it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FirstLines {
+    pub left: usize,
+    pub right: usize,
+}
+
+impl Default for FirstLines {
+    fn default() -> Self {
+        Self { left: 1, right: 1 }
+    }
+}
+
+/// How a [Diff] is computed. Unlike [colored_diff], which follows the global
+/// [config](crate::config), a [Diff] only depends on its options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffOptions {
+    /// Number of unchanged lines kept around every change, `usize::MAX` keeps all of them
+    pub context: usize,
+    pub algorithm: DiffAlgorithm,
+    pub first_lines: FirstLines,
+}
+
+impl DiffOptions {
+    /// Options set with [config](crate::config) functions and `K9_*` environment
+    /// variables, the ones assertion failures use
+    pub fn from_config() -> Self {
+        Self {
+            context: config::diff_context_lines(),
+            algorithm: config::diff_algorithm(),
+            first_lines: FirstLines::default(),
+        }
+    }
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            context: 3,
+            algorithm: DiffAlgorithm::Myers,
+            first_lines: FirstLines::default(),
+        }
+    }
+}
+
+/// One of the two compared values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineOp {
+    /// The line is in both values
+    Unchanged,
+    /// The line is only in the left value
+    Removed,
+    /// The line is only in the right value
+    Added,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub op: LineOp,
+    /// Text of the line without the `\n`. The `\r` of `\r\n` line endings is kept
+    pub text: String,
+    /// Number of the line in the left value, `None` for added lines. Values are split
+    /// on `\n`, so the ones ending with a newline have an empty last line, which is
+    /// not numbered since it's not really there
+    pub left_number: Option<usize>,
+    /// Number of the line in the right value, `None` for removed lines
+    pub right_number: Option<usize>,
+    /// Byte ranges of `text` that differ from the line it's paired with. The first
+    /// removed line of a change is paired with the first added line and so on.
+    /// Empty for unchanged lines and lines that have nothing in common with their pair
+    pub changed_ranges: Vec<Range<usize>>,
+}
+
+/// Changed lines together with the unchanged lines around them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// Number of unchanged lines between the previous hunk (or the start of
+    /// the values) and this one. They aren't part of any hunk
+    pub skipped: usize,
+    /// Number of the first left line of the hunk, or of the left line
+    /// following it if the hunk has no left lines
+    pub left_start: usize,
+    /// Same as [Hunk::left_start] for the right value
+    pub right_start: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    pub added: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+/// Difference between two strings, line by line. Unchanged lines further than
+/// [DiffOptions::context] lines from any change are left out of the hunks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diff {
+    pub hunks: Vec<Hunk>,
+    /// Number of unchanged lines after the last hunk
+    pub skipped_at_end: usize,
+    /// Value that doesn't end with a newline while the other one does. A missing
+    /// final newline is reported on its own instead of as a changed line
+    pub missing_newline: Option<Side>,
+    pub stats: DiffStats,
+}
+
+impl Diff {
+    /// Diff with [DiffOptions::default]
+    pub fn new(left: &str, right: &str) -> Self {
+        Self::with_options(left, right, DiffOptions::default())
+    }
+
+    pub fn with_options(left: &str, right: &str, options: DiffOptions) -> Self {
+        let (left_body, right_body, missing_newline) =
+            match (left.strip_suffix('\n'), right.strip_suffix('\n')) {
+                (Some(left_body), None) => (left_body, right, Some(Side::Right)),
+                (None, Some(right_body)) => (left, right_body, Some(Side::Left)),
+                _ => (left, right, None),
+            };
+        let lines = numbered_lines(left_body, right_body, options);
+
+        let mut stats = DiffStats::default();
+        for (line, _) in &lines {
+            match line.op {
+                LineOp::Added => stats.added += 1,
+                LineOp::Removed => stats.removed += 1,
+                LineOp::Unchanged if line.left_number.is_some() => stats.unchanged += 1,
+                LineOp::Unchanged => {}
+            }
+        }
+
+        let (hunks, skipped_at_end) =
+            if stats.added + stats.removed == 0 && missing_newline.is_none() {
+                (vec![], lines.len())
+            } else {
+                hunks(lines, options.context, missing_newline.is_some())
+            };
+
+        Self {
+            hunks,
+            skipped_at_end,
+            missing_newline,
+            stats,
+        }
+    }
+
+    /// Whether the values are the same
+    pub fn is_empty(&self) -> bool {
+        self.hunks.is_empty() && self.missing_newline.is_none()
+    }
+
+    /// Whether the values differ only in whitespace
+    pub fn is_whitespace_only(&self) -> bool {
+        // Unchanged lines are the same on both sides, so it's enough to compare
+        // the changed ones
+        let visible = |op: LineOp| {
+            self.hunks
+                .iter()
+                .flat_map(|hunk| &hunk.lines)
+                .filter(|line| line.op == op)
+                .flat_map(|line| line.text.chars())
+                .filter(|c| !c.is_whitespace())
+                .collect::<String>()
+        };
+        !self.is_empty() && visible(LineOp::Removed) == visible(LineOp::Added)
+    }
+}
 
 pub fn colored_diff(left: &str, right: &str) -> Option<String> {
-    let mut result = String::new();
+    colored_diff_at(left, right, FirstLines::default())
+}
 
+/// Same as [colored_diff], but line numbers (if enabled with
+/// [set_diff_line_numbers](crate::config::set_diff_line_numbers)) start at `first_lines`
+pub fn colored_diff_at(left: &str, right: &str, first_lines: FirstLines) -> Option<String> {
     if left == right {
         return None;
     }
+    let options = DiffOptions {
+        first_lines,
+        ..DiffOptions::from_config()
+    };
+    Some(render_colored(&Diff::with_options(left, right, options)))
+}
+
+/// All lines of both values in diff order
+fn numbered_lines(left: &str, right: &str, options: DiffOptions) -> Vec<NumberedLine> {
+    let first_lines = options.first_lines;
+    let left_end = first_lines.left + left.lines().count();
+    let right_end = first_lines.right + right.lines().count();
+    let (mut left_number, mut right_number) = (first_lines.left, first_lines.right);
+
+    let mut lines = vec![];
+    let line = |op, text: &str, left: Option<usize>, right: Option<usize>| DiffLine {
+        op,
+        text: text.to_string(),
+        left_number: left.filter(|number| *number < left_end),
+        right_number: right.filter(|number| *number < right_end),
+        changed_ranges: vec![],
+    };
+    for edit in sequence_diff::lines(left, right, options.algorithm) {
+        let position = (left_number, right_number);
+        let diff_line = match edit {
+            Edit::Left(l) => {
+                left_number += 1;
+                line(LineOp::Removed, l, Some(position.0), None)
+            }
+            Edit::Right(r) => {
+                right_number += 1;
+                line(LineOp::Added, r, None, Some(position.1))
+            }
+            Edit::Both(l, _r) => {
+                left_number += 1;
+                right_number += 1;
+                line(LineOp::Unchanged, l, Some(position.0), Some(position.1))
+            }
+        };
+        lines.push((diff_line, position));
+    }
+    pair_changed_lines(&mut lines, options.algorithm);
+    lines
+}
+
+/// Fill in [DiffLine::changed_ranges] of every removed line and the added line
+/// in the same position of the same change
+fn pair_changed_lines(lines: &mut [NumberedLine], algorithm: DiffAlgorithm) {
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].0.op == LineOp::Unchanged {
+            i += 1;
+            continue;
+        }
+        // A change is removed lines followed by added lines
+        let removed_start = i;
+        while i < lines.len() && lines[i].0.op == LineOp::Removed {
+            i += 1;
+        }
+        let added_start = i;
+        while i < lines.len() && lines[i].0.op == LineOp::Added {
+            i += 1;
+        }
+
+        let pairs = (added_start - removed_start).min(i - added_start);
+        for pair in 0..pairs {
+            let (l, r) = (removed_start + pair, added_start + pair);
+            if let Some((left_ranges, right_ranges)) =
+                changed_ranges(&lines[l].0.text, &lines[r].0.text, algorithm)
+            {
+                lines[l].0.changed_ranges = left_ranges;
+                lines[r].0.changed_ranges = right_ranges;
+            }
+        }
+    }
+}
+
+/// Group lines into hunks, leaving out runs of unchanged lines that are more
+/// than `context` lines away from any change. Returns the hunks and the number
+/// of unchanged lines after the last one
+fn hunks(lines: Vec<NumberedLine>, context: usize, missing_newline: bool) -> (Vec<Hunk>, usize) {
+    let mut hunks: Vec<Hunk> = vec![];
+    let mut current: Option<Hunk> = None;
+    let mut skipped = 0;
+
+    let len = lines.len();
+    let mut lines = lines.into_iter().peekable();
+    let mut i = 0;
+    while let Some(line) = lines.next() {
+        if line.0.op != LineOp::Unchanged {
+            push_line(&mut current, &mut skipped, line);
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let mut run = vec![line];
+        while let Some(line) = lines.next_if(|(line, _)| line.op == LineOp::Unchanged) {
+            run.push(line);
+        }
+        i += run.len();
+
+        // The missing newline is a change after the last line
+        let keep_before = if start > 0 { context } else { 0 };
+        let keep_after = if i < len || missing_newline {
+            context
+        } else {
+            0
+        };
+        let collapsed = run
+            .len()
+            .saturating_sub(keep_before.saturating_add(keep_after));
+
+        if collapsed >= MIN_COLLAPSED_LINES {
+            let after = run.split_off(keep_before + collapsed);
+            run.truncate(keep_before);
+            for line in run {
+                push_line(&mut current, &mut skipped, line);
+            }
+            hunks.extend(current.take());
+            skipped = collapsed;
+            for line in after {
+                push_line(&mut current, &mut skipped, line);
+            }
+        } else {
+            for line in run {
+                push_line(&mut current, &mut skipped, line);
+            }
+        }
+    }
+    hunks.extend(current);
+    (hunks, skipped)
+}
+
+/// Add a line to the current hunk, starting a new one if there's none.
+/// `skipped` is taken by the new hunk
+fn push_line(current: &mut Option<Hunk>, skipped: &mut usize, line: NumberedLine) {
+    let (line, (left_start, right_start)) = line;
+    current
+        .get_or_insert_with(|| Hunk {
+            skipped: std::mem::take(skipped),
+            left_start,
+            right_start,
+            lines: vec![],
+        })
+        .lines
+        .push(line);
+}
+
+/// E.g. `⋯ 1,234 unchanged lines ⋯`. `unit` is what was skipped, e.g. `line`
+fn skipped_description(skipped: usize, unit: &str) -> String {
+    let digits = skipped.to_string();
+    // digits are ASCII, so groups of three bytes are groups of three characters
+    let mut groups = digits
+        .as_bytes()
+        .rchunks(3)
+        .map(|group| std::str::from_utf8(group).unwrap())
+        .collect::<Vec<_>>();
+    groups.reverse();
+    let count = groups.join(",");
+    let plural = if skipped == 1 { "" } else { "s" };
+    format!("⋯ {} unchanged {}{} ⋯", count, unit, plural)
+}
+
+/// Whether two strings are different, but only in whitespace
+fn whitespace_only(left: &str, right: &str) -> bool {
+    let visible = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    left != right && visible(left) == visible(right)
+}
+
+/// Byte ranges of words that differ between two versions of the same line.
+/// Returns `None` if the lines have nothing but whitespace in common, since
+/// highlighting all of it wouldn't be useful.
+pub(crate) fn changed_ranges(
+    left: &str,
+    right: &str,
+    algorithm: DiffAlgorithm,
+) -> Option<(Ranges, Ranges)> {
+    let left_tokens = tokenize(left);
+    let right_tokens = tokenize(right);
+    if left_tokens.len() > MAX_HIGHLIGHTED_TOKENS || right_tokens.len() > MAX_HIGHLIGHTED_TOKENS {
+        return None;
+    }
+
+    let left_words = left_tokens
+        .iter()
+        .map(|r| &left[r.clone()])
+        .collect::<Vec<_>>();
+    let right_words = right_tokens
+        .iter()
+        .map(|r| &right[r.clone()])
+        .collect::<Vec<_>>();
+
+    let mut left_ranges: Ranges = vec![];
+    let mut right_ranges: Ranges = vec![];
+    let mut unchanged = 0;
+    let (mut l, mut r) = (0, 0);
+    for token in sequence_diff::slice_with(&left_words, &right_words, algorithm) {
+        match token {
+            Edit::Left(_) => {
+                push_range(&mut left_ranges, left_tokens[l].clone());
+                l += 1;
+            }
+            Edit::Right(_) => {
+                push_range(&mut right_ranges, right_tokens[r].clone());
+                r += 1;
+            }
+            Edit::Both(word, _) => {
+                if !word.trim().is_empty() {
+                    unchanged += 1;
+                }
+                l += 1;
+                r += 1;
+            }
+        }
+    }
+
+    if unchanged == 0 {
+        return None;
+    }
+    Some((left_ranges, right_ranges))
+}
+
+/// Merge adjacent ranges, so that a changed phrase is highlighted as a whole
+fn push_range(ranges: &mut Ranges, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
+/// Split a line into words (runs of alphanumeric characters), runs of whitespace
+/// and single punctuation characters
+fn tokenize(line: &str) -> Ranges {
+    #[derive(PartialEq)]
+    enum Kind {
+        Word,
+        Whitespace,
+        Other,
+    }
 
-    let lines = lines(left, right);
-    result.push('\n');
-    for line in lines {
-        match line {
-            Result::Left(l) => {
-                writeln!(result, "{} {}", "-".red(), &l.red()).unwrap();
+    let kind = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            Kind::Word
+        } else if c.is_whitespace() {
+            Kind::Whitespace
+        } else {
+            Kind::Other
+        }
+    };
+
+    let mut tokens: Ranges = vec![];
+    let mut previous = None;
+    for (i, c) in line.char_indices() {
+        let current = kind(c);
+        match tokens.last_mut() {
+            Some(last) if current != Kind::Other && previous.as_ref() == Some(&current) => {
+                last.end = i + c.len_utf8()
             }
-            Result::Right(r) => {
-                writeln!(result, "{} {}", "+".green(), &r.green()).unwrap();
+            _ => tokens.push(i..i + c.len_utf8()),
+        }
+        previous = Some(current);
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changed_words(left: &str, right: &str) -> Option<(Vec<String>, Vec<String>)> {
+        changed_ranges(left, right, DiffAlgorithm::Myers).map(|(l, r)| {
+            (
+                l.into_iter().map(|r| left[r].to_string()).collect(),
+                r.into_iter().map(|r| right[r].to_string()).collect(),
+            )
+        })
+    }
+
+    #[test]
+    fn highlights_changed_words() {
+        k9_stable::assert_equal!(
+            changed_words(
+                r#"    address: "221B Baker Street", zip: 12345,"#,
+                r#"    address: "221B Baker Street", zip: 12346,"#
+            ),
+            Some((vec!["12345".to_string()], vec!["12346".to_string()]))
+        );
+        k9_stable::assert_equal!(
+            changed_words("let x = foo(a, b);", "let x = foo(a, c, d);"),
+            Some((vec!["b".to_string()], vec!["c, d".to_string()]))
+        );
+        k9_stable::assert_equal!(
+            changed_words("Привет мир", "Привет, мир"),
+            Some((vec![], vec![",".to_string()]))
+        );
+    }
+
+    fn collapsed(left: &str, right: &str, context: usize) -> Vec<String> {
+        let (hunks, skipped_at_end) = hunks(
+            numbered_lines(left, right, DiffOptions::default()),
+            context,
+            false,
+        );
+        let mut result = vec![];
+        for hunk in hunks {
+            if hunk.skipped > 0 {
+                result.push(format!("<{}>", hunk.skipped));
             }
-            Result::Both(l, _r) => {
-                writeln!(result, "  {}", &l.dimmed()).unwrap();
+            for line in hunk.lines {
+                let marker = match line.op {
+                    LineOp::Unchanged => "",
+                    LineOp::Removed => "-",
+                    LineOp::Added => "+",
+                };
+                result.push(format!("{}{}", marker, line.text));
             }
         }
+        if skipped_at_end > 0 {
+            result.push(format!("<{}>", skipped_at_end));
+        }
+        result
+    }
+
+    #[test]
+    fn collapses_unchanged_lines() {
+        let left = (1..=20).map(|i| i.to_string()).collect::<Vec<_>>();
+        let mut right = left.clone();
+        right[9] = "x".to_string();
+        let (left, right) = (left.join("\n"), right.join("\n"));
+
+        k9_stable::assert_equal!(
+            collapsed(&left, &right, 2),
+            vec!["<7>", "8", "9", "-10", "+x", "11", "12", "<8>"]
+        );
+        // not worth collapsing
+        k9_stable::assert_equal!(collapsed(&left, &right, 7).len(), 21);
+        k9_stable::assert_equal!(collapsed(&left, &right, usize::MAX).len(), 21);
+    }
+
+    #[test]
+    fn skips_unrelated_lines() {
+        k9_stable::assert_equal!(changed_words("hello world", "goodbye moon"), None);
+        k9_stable::assert_equal!(changed_words("    [", "    }"), None);
+    }
+
+    #[test]
+    fn groups_thousands() {
+        k9_stable::assert_equal!(skipped_description(1, "line"), "⋯ 1 unchanged line ⋯");
+        k9_stable::assert_equal!(
+            skipped_description(123, "character"),
+            "⋯ 123 unchanged characters ⋯"
+        );
+        k9_stable::assert_equal!(
+            skipped_description(1234567, "line"),
+            "⋯ 1,234,567 unchanged lines ⋯"
+        );
     }
-    Some(result)
 }
@@ -0,0 +1,87 @@
+use super::terminal::MISSING_NEWLINE;
+use super::{skipped_description, Diff, DiffLine, LineOp, Side};
+use std::fmt::Write;
+
+/// Render the diff as HTML. Every line is a `<div>` inside of `<pre class="k9-diff">`
+/// with a `k9-unchanged`, `k9-removed`, `k9-added` or `k9-skipped` class and
+/// `data-left`/`data-right` line numbers. Changed parts of lines are wrapped in `<mark>`.
+/// No styles are included, so the diff can be styled to match the page it's on
+pub fn render_html(diff: &Diff) -> String {
+    let mut result = String::from("<pre class=\"k9-diff\">\n");
+    for hunk in &diff.hunks {
+        if hunk.skipped > 0 {
+            write_skipped(&mut result, hunk.skipped);
+        }
+        // The empty line after a trailing newline isn't a real line
+        let lines = hunk
+            .lines
+            .iter()
+            .filter(|line| line.left_number.is_some() || line.right_number.is_some());
+        for line in lines {
+            write_line(&mut result, line);
+        }
+    }
+    if diff.skipped_at_end > 0 {
+        write_skipped(&mut result, diff.skipped_at_end);
+    }
+    let missing_newline = match diff.missing_newline {
+        Some(Side::Left) => Some(("k9-removed", '-')),
+        Some(Side::Right) => Some(("k9-added", '+')),
+        None => None,
+    };
+    if let Some((class, marker)) = missing_newline {
+        writeln!(
+            result,
+            "<div class=\"{}\">{} <mark>{}</mark></div>",
+            class, marker, MISSING_NEWLINE
+        )
+        .unwrap();
+    }
+    result.push_str("</pre>\n");
+    result
+}
+
+fn write_line(result: &mut String, line: &DiffLine) {
+    let (class, marker) = match line.op {
+        LineOp::Unchanged => ("k9-unchanged", ' '),
+        LineOp::Removed => ("k9-removed", '-'),
+        LineOp::Added => ("k9-added", '+'),
+    };
+    write!(result, "<div class=\"{}\"", class).unwrap();
+    if let Some(number) = line.left_number {
+        write!(result, " data-left=\"{}\"", number).unwrap();
+    }
+    if let Some(number) = line.right_number {
+        write!(result, " data-right=\"{}\"", number).unwrap();
+    }
+    write!(result, ">{} ", marker).unwrap();
+
+    let mut pos = 0;
+    for range in &line.changed_ranges {
+        result.push_str(&escape(&line.text[pos..range.start]));
+        write!(result, "<mark>{}</mark>", escape(&line.text[range.clone()])).unwrap();
+        pos = range.end;
+    }
+    result.push_str(&escape(&line.text[pos..]));
+    result.push_str("</div>\n");
+}
+
+fn write_skipped(result: &mut String, skipped: usize) {
+    let description = skipped_description(skipped, "line");
+    writeln!(result, "<div class=\"k9-skipped\">{}</div>", description).unwrap();
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
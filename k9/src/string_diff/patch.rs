@@ -0,0 +1,83 @@
+use super::{Diff, DiffLine, LineOp, Side};
+use std::fmt::Write;
+
+const NO_NEWLINE: &str = "\\ No newline at end of file";
+
+/// Render the diff as a unified patch, the format of `diff -u` and `git diff`,
+/// which can be applied with `patch` or `git apply`. `left_name` and `right_name`
+/// are printed in the `---` and `+++` headers
+pub fn render_unified_patch(diff: &Diff, left_name: &str, right_name: &str) -> String {
+    let mut result = String::new();
+    if diff.is_empty() {
+        return result;
+    }
+    writeln!(result, "--- {}", left_name).unwrap();
+    writeln!(result, "+++ {}", right_name).unwrap();
+
+    for (i, hunk) in diff.hunks.iter().enumerate() {
+        // The empty line after a trailing newline isn't a real line
+        let lines = hunk
+            .lines
+            .iter()
+            .filter(|line| line.left_number.is_some() || line.right_number.is_some())
+            .collect::<Vec<_>>();
+        let left_len = lines.iter().filter(|l| l.left_number.is_some()).count();
+        let right_len = lines.iter().filter(|l| l.right_number.is_some()).count();
+        writeln!(
+            result,
+            "@@ -{} +{} @@",
+            range(hunk.left_start, left_len),
+            range(hunk.right_start, right_len)
+        )
+        .unwrap();
+
+        // Lines after the last hunk are unchanged, so the missing newline is
+        // in the last hunk unless there are some
+        let at_end = i == diff.hunks.len() - 1 && diff.skipped_at_end == 0;
+        let missing_newline = diff.missing_newline.filter(|_| at_end);
+        let last_left = lines.iter().rev().find_map(|l| l.left_number);
+        let last_right = lines.iter().rev().find_map(|l| l.right_number);
+
+        for line in lines {
+            let last_in = |side| match side {
+                Side::Left => line.left_number.is_some() && line.left_number == last_left,
+                Side::Right => line.right_number.is_some() && line.right_number == last_right,
+            };
+            match (line.op, missing_newline) {
+                // The last line differs in the newline, so it's changed as far
+                // as the patch is concerned
+                (LineOp::Unchanged, Some(side)) if last_in(side) => {
+                    write_line(&mut result, '-', line, side == Side::Left);
+                    write_line(&mut result, '+', line, side == Side::Right);
+                }
+                (LineOp::Unchanged, _) => write_line(&mut result, ' ', line, false),
+                (LineOp::Removed, side) => {
+                    let no_newline = side == Some(Side::Left) && last_in(Side::Left);
+                    write_line(&mut result, '-', line, no_newline);
+                }
+                (LineOp::Added, side) => {
+                    let no_newline = side == Some(Side::Right) && last_in(Side::Right);
+                    write_line(&mut result, '+', line, no_newline);
+                }
+            }
+        }
+    }
+    result
+}
+
+fn write_line(result: &mut String, marker: char, line: &DiffLine, no_newline: bool) {
+    writeln!(result, "{}{}", marker, line.text).unwrap();
+    if no_newline {
+        writeln!(result, "{}", NO_NEWLINE).unwrap();
+    }
+}
+
+/// Lines of one value a hunk header refers to. Empty ranges point at the line
+/// before them
+fn range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{},0", start - 1),
+        1 => start.to_string(),
+        len => format!("{},{}", start, len),
+    }
+}
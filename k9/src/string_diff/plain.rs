@@ -0,0 +1,42 @@
+use super::terminal::MISSING_NEWLINE;
+use super::{skipped_description, Diff, LineOp, Side};
+use std::fmt::Write;
+
+/// Render the diff as plain unified text with `-`/`+` markers, e.g. for writing
+/// to a file. Unlike [render_colored](super::render_colored), it doesn't depend
+/// on the terminal or [config](crate::config)
+pub fn render_plain(diff: &Diff) -> String {
+    let mut result = String::new();
+    for hunk in &diff.hunks {
+        if hunk.skipped > 0 {
+            writeln!(result, "  {}", skipped_description(hunk.skipped, "line")).unwrap();
+        }
+        // The empty line after a trailing newline isn't a real line
+        let lines = hunk
+            .lines
+            .iter()
+            .filter(|line| line.left_number.is_some() || line.right_number.is_some());
+        for line in lines {
+            let marker = match line.op {
+                LineOp::Unchanged => ' ',
+                LineOp::Removed => '-',
+                LineOp::Added => '+',
+            };
+            writeln!(result, "{} {}", marker, line.text).unwrap();
+        }
+    }
+    if diff.skipped_at_end > 0 {
+        writeln!(
+            result,
+            "  {}",
+            skipped_description(diff.skipped_at_end, "line")
+        )
+        .unwrap();
+    }
+    match diff.missing_newline {
+        Some(Side::Left) => writeln!(result, "- {}", MISSING_NEWLINE).unwrap(),
+        Some(Side::Right) => writeln!(result, "+ {}", MISSING_NEWLINE).unwrap(),
+        None => {}
+    }
+    result
+}
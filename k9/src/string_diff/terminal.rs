@@ -0,0 +1,690 @@
+use super::{skipped_description, whitespace_only, Diff, DiffLine, LineOp, Side};
+use crate::config::{diff_layout, DiffLayout};
+use crate::sequence_diff::{self, Edit};
+use colored::*;
+use std::fmt::Write;
+use std::ops::Range;
+
+/// Side by side diff falls back to the unified one if the columns would be
+/// narrower than this
+const MIN_SIDE_BY_SIDE_COLUMN_WIDTH: usize = 30;
+
+/// A changed line longer than this (in characters) that replaced a single other
+/// line is compared character by character, see [write_long_lines]
+const LONG_LINE_THRESHOLD: usize = 200;
+
+/// Number of unchanged characters printed around every change of a long line
+const LONG_LINE_CONTEXT: usize = 40;
+
+/// Unchanged characters of a long line are only collapsed if there are at
+/// least this many of them
+const MIN_COLLAPSED_CHARS: usize = 10;
+
+/// Long lines are never wrapped into chunks narrower than this
+const MIN_CHUNK_WIDTH: usize = 20;
+
+/// Printed in place of a final newline that only one of the values has
+pub(super) const MISSING_NEWLINE: &str = "⏎ missing newline at end";
+
+/// Byte ranges within a line
+type Ranges = Vec<Range<usize>>;
+
+/// Characters of two long lines aligned with each other. Either side is `None`
+/// if the character only exists in the other line
+type Column = (Option<char>, Option<char>);
+
+/// Consecutive lines of the diff
+enum Block<'a> {
+    Unchanged(&'a DiffLine),
+    /// Number of unchanged lines that are too far from any change to be printed
+    Collapsed(usize),
+    /// Removed lines together with the added lines that replaced them, so every
+    /// removed line can be paired with the added one
+    Changed {
+        removed: Vec<&'a DiffLine>,
+        added: Vec<&'a DiffLine>,
+    },
+    /// Only one of the values ends with a newline. `in_left` is `true` if it's
+    /// the left one that doesn't
+    MissingNewline {
+        in_left: bool,
+    },
+}
+
+/// Line numbers printed in front of the lines of the diff
+struct Gutter {
+    /// Width of a single line number, `None` if line numbers are disabled
+    width: Option<usize>,
+}
+
+impl Gutter {
+    fn new(diff: &Diff) -> Self {
+        let largest = diff
+            .hunks
+            .iter()
+            .flat_map(|hunk| &hunk.lines)
+            .flat_map(|line| line.left_number.into_iter().chain(line.right_number))
+            .max()
+            .unwrap_or(0);
+        Self {
+            width: Some(largest.to_string().len()).filter(|_| crate::config::diff_line_numbers()),
+        }
+    }
+
+    /// Line number padded to the width of the gutter
+    fn number(&self, number: Option<usize>) -> String {
+        let width = self.width.unwrap_or(0);
+        match number {
+            Some(number) => format!("{:>width$}", number, width = width),
+            None => " ".repeat(width),
+        }
+    }
+
+    /// Line numbers in front of a line of the unified diff
+    fn prefix(&self, left: Option<usize>, right: Option<usize>) -> String {
+        match self.width {
+            Some(_) => format!(
+                "{} {} {} ",
+                self.number(left).dimmed(),
+                self.number(right).dimmed(),
+                "│".dimmed()
+            ),
+            None => String::new(),
+        }
+    }
+
+    /// Number of characters taken by [Gutter::prefix]
+    fn prefix_width(&self) -> usize {
+        self.width.map_or(0, |width| width * 2 + 4)
+    }
+
+    /// Line number in front of a column of the side by side diff
+    fn column_prefix(&self, number: Option<usize>) -> String {
+        match self.width {
+            Some(_) => format!("{} ", self.number(number).dimmed()),
+            None => String::new(),
+        }
+    }
+
+    /// Number of characters taken by [Gutter::column_prefix]
+    fn column_prefix_width(&self) -> usize {
+        self.width.map_or(0, |width| width + 1)
+    }
+}
+
+/// Render the diff for printing in a terminal, the way assertion failures show it.
+/// Lays it out according to [diff_layout](crate::config::diff_layout)
+pub fn render_colored(diff: &Diff) -> String {
+    let mut result = String::new();
+    let blocks = blocks(diff);
+    let gutter = Gutter::new(diff);
+    result.push('\n');
+    if diff.is_whitespace_only() {
+        writeln!(
+            result,
+            "{}\n",
+            "Difference is whitespace only (· space, → tab, ␍ carriage return)".yellow()
+        )
+        .unwrap();
+    }
+    match side_by_side_column_width(&gutter) {
+        Some(width) => write_side_by_side(&mut result, &blocks, width, &gutter),
+        None => write_unified(&mut result, &blocks, &gutter),
+    }
+    result
+}
+
+fn blocks(diff: &Diff) -> Vec<Block<'_>> {
+    let mut blocks = vec![];
+    let mut removed = vec![];
+    let mut added = vec![];
+    for hunk in &diff.hunks {
+        if hunk.skipped > 0 {
+            push_changed(&mut blocks, &mut removed, &mut added);
+            blocks.push(Block::Collapsed(hunk.skipped));
+        }
+        for line in &hunk.lines {
+            match line.op {
+                LineOp::Removed => {
+                    if !added.is_empty() {
+                        push_changed(&mut blocks, &mut removed, &mut added);
+                    }
+                    removed.push(line);
+                }
+                LineOp::Added => added.push(line),
+                LineOp::Unchanged => {
+                    push_changed(&mut blocks, &mut removed, &mut added);
+                    blocks.push(Block::Unchanged(line));
+                }
+            }
+        }
+    }
+    push_changed(&mut blocks, &mut removed, &mut added);
+    if diff.skipped_at_end > 0 {
+        blocks.push(Block::Collapsed(diff.skipped_at_end));
+    }
+    if let Some(side) = diff.missing_newline {
+        blocks.push(Block::MissingNewline {
+            in_left: side == Side::Left,
+        });
+    }
+    blocks
+}
+
+fn push_changed<'a>(
+    blocks: &mut Vec<Block<'a>>,
+    removed: &mut Vec<&'a DiffLine>,
+    added: &mut Vec<&'a DiffLine>,
+) {
+    if !removed.is_empty() || !added.is_empty() {
+        blocks.push(Block::Changed {
+            removed: std::mem::take(removed),
+            added: std::mem::take(added),
+        });
+    }
+}
+
+fn collapsed_separator(collapsed: usize, unit: &str) -> ColoredString {
+    skipped_description(collapsed, unit).dimmed()
+}
+
+/// Whether whitespace in the changed `line` should be made visible, which is
+/// the case if it's the only thing that changed. `other` is the line it's paired with
+fn shows_whitespace(line: &DiffLine, other: Option<&&DiffLine>) -> bool {
+    match other {
+        Some(other) => whitespace_only(&line.text, &other.text),
+        None => line.text.trim().is_empty(),
+    }
+}
+
+/// Replace whitespace characters with visible markers
+fn show_whitespace(text: &str) -> String {
+    text.chars().map(visible_char).collect()
+}
+
+/// Lines keep the `\r` of `\r\n` line endings, but printing it would move
+/// the cursor, so it's dropped unless whitespace is shown
+fn without_carriage_return(line: &str) -> &str {
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+fn visible_char(c: char) -> char {
+    match c {
+        ' ' => '·',
+        '\t' => '→',
+        '\r' => '␍',
+        c => c,
+    }
+}
+
+fn write_unified(result: &mut String, blocks: &[Block], gutter: &Gutter) {
+    for block in blocks {
+        match block {
+            Block::Unchanged(l) => {
+                let prefix = gutter.prefix(l.left_number, l.right_number);
+                let line = without_carriage_return(&l.text).dimmed();
+                writeln!(result, "{}  {}", prefix, line).unwrap();
+            }
+            Block::Collapsed(lines) => {
+                let prefix = gutter.prefix(None, None);
+                writeln!(
+                    result,
+                    "{}  {}",
+                    prefix,
+                    collapsed_separator(*lines, "line")
+                )
+                .unwrap();
+            }
+            Block::Changed { removed, added } => {
+                if is_long_line_change(removed, added) {
+                    write_long_lines(result, removed[0], added[0], gutter);
+                    continue;
+                }
+
+                for (i, l) in removed.iter().enumerate() {
+                    let visible = shows_whitespace(l, added.get(i));
+                    let line = highlight(
+                        &l.text,
+                        &l.changed_ranges,
+                        visible,
+                        |s| s.red(),
+                        |s| s.white().on_red(),
+                    );
+                    let prefix = gutter.prefix(l.left_number, None);
+                    writeln!(result, "{}{} {}", prefix, "-".red(), line).unwrap();
+                }
+                for (i, r) in added.iter().enumerate() {
+                    let visible = shows_whitespace(r, removed.get(i));
+                    let line = highlight(
+                        &r.text,
+                        &r.changed_ranges,
+                        visible,
+                        |s| s.green(),
+                        |s| s.black().on_green(),
+                    );
+                    let prefix = gutter.prefix(None, r.right_number);
+                    writeln!(result, "{}{} {}", prefix, "+".green(), line).unwrap();
+                }
+            }
+            Block::MissingNewline { in_left: true } => {
+                let prefix = gutter.prefix(None, None);
+                writeln!(result, "{}{} {}", prefix, "-".red(), MISSING_NEWLINE.red()).unwrap();
+            }
+            Block::MissingNewline { in_left: false } => {
+                let prefix = gutter.prefix(None, None);
+                writeln!(
+                    result,
+                    "{}{} {}",
+                    prefix,
+                    "+".green(),
+                    MISSING_NEWLINE.green()
+                )
+                .unwrap();
+            }
+        }
+    }
+}
+
+/// Whether a single line was replaced with another one and either of them is
+/// too long to read as a whole, e.g. minified JSON, SQL or a URL
+fn is_long_line_change(removed: &[&DiffLine], added: &[&DiffLine]) -> bool {
+    match (removed, added) {
+        ([l], [r]) => l.text.chars().count().max(r.text.chars().count()) > LONG_LINE_THRESHOLD,
+        _ => false,
+    }
+}
+
+/// Print a long line and the line that replaced it as aligned chunks with
+/// carets under the characters that differ. Only the parts within
+/// [LONG_LINE_CONTEXT] characters of a change are printed
+fn write_long_lines(result: &mut String, left: &DiffLine, right: &DiffLine, gutter: &Gutter) {
+    let columns = align_chars(&left.text, &right.text);
+    // `- ` markers in front of the chunks
+    let width = crate::utils::terminal_width()
+        .saturating_sub(gutter.prefix_width() + 2)
+        .max(MIN_CHUNK_WIDTH);
+    let blank = gutter.prefix(None, None);
+
+    let mut position = 0;
+    // Line numbers go on the first printed chunk, which doesn't start at
+    // the beginning of the line if its start is collapsed
+    let mut first = true;
+    for window in change_windows(&columns, LONG_LINE_CONTEXT) {
+        if position < window.start {
+            let separator = collapsed_separator(window.start - position, "character");
+            writeln!(result, "{}  {}", blank, separator).unwrap();
+        }
+        for start in window.clone().step_by(width) {
+            let chunk = &columns[start..(start + width).min(window.end)];
+            let (left_number, right_number) = if first {
+                (left.left_number, right.right_number)
+            } else {
+                (None, None)
+            };
+            first = false;
+            let left_chunk = render_chars(chunk, |(l, _)| l, |s| s.red(), |s| s.white().on_red());
+            let right_chunk =
+                render_chars(chunk, |(_, r)| r, |s| s.green(), |s| s.black().on_green());
+            let carets = chunk
+                .iter()
+                .map(|(l, r)| if l == r { ' ' } else { '^' })
+                .collect::<String>();
+
+            let prefix = gutter.prefix(left_number, None);
+            writeln!(result, "{}{} {}", prefix, "-".red(), left_chunk).unwrap();
+            let prefix = gutter.prefix(None, right_number);
+            writeln!(result, "{}{} {}", prefix, "+".green(), right_chunk).unwrap();
+            writeln!(result, "{}  {}", blank, carets.trim_end().yellow()).unwrap();
+        }
+        position = window.end;
+    }
+    if position < columns.len() {
+        let separator = collapsed_separator(columns.len() - position, "character");
+        writeln!(result, "{}  {}", blank, separator).unwrap();
+    }
+}
+
+fn align_chars(left: &str, right: &str) -> Vec<Column> {
+    let left = left.chars().collect::<Vec<_>>();
+    let right = right.chars().collect::<Vec<_>>();
+    sequence_diff::slice(&left, &right)
+        .into_iter()
+        .map(|edit| match edit {
+            Edit::Left(l) => (Some(*l), None),
+            Edit::Right(r) => (None, Some(*r)),
+            Edit::Both(l, r) => (Some(*l), Some(*r)),
+        })
+        .collect()
+}
+
+/// Ranges of columns that are at most `context` columns away from a change
+fn change_windows(columns: &[Column], context: usize) -> Vec<Range<usize>> {
+    let mut windows: Vec<Range<usize>> = vec![];
+    for (i, (l, r)) in columns.iter().enumerate() {
+        if l == r {
+            continue;
+        }
+        let start = i.saturating_sub(context);
+        let end = (i + 1).saturating_add(context).min(columns.len());
+        match windows.last_mut() {
+            // not worth collapsing what's between the windows
+            Some(last) if start < last.end + MIN_COLLAPSED_CHARS => last.end = end,
+            _ => windows.push(start..end),
+        }
+    }
+    if let Some(first) = windows.first_mut() {
+        if first.start < MIN_COLLAPSED_CHARS {
+            first.start = 0;
+        }
+    }
+    if let Some(last) = windows.last_mut() {
+        if columns.len() - last.end < MIN_COLLAPSED_CHARS {
+            last.end = columns.len();
+        }
+    }
+    windows
+}
+
+/// One side of aligned columns, with characters the other side doesn't have
+/// highlighted. Tabs and carriage returns are replaced with visible markers,
+/// since they would break the alignment
+fn render_chars<S, F, H>(columns: &[Column], side: S, color: F, highlight: H) -> String
+where
+    S: Fn(&Column) -> &Option<char>,
+    F: Fn(&str) -> ColoredString,
+    H: Fn(&str) -> ColoredString,
+{
+    // Gaps at the end are dropped to avoid trailing whitespace
+    let len = columns
+        .iter()
+        .rposition(|column| side(column).is_some())
+        .map_or(0, |i| i + 1);
+
+    let mut segments: Vec<(String, bool)> = vec![];
+    for column in &columns[..len] {
+        let (c, highlighted) = match side(column) {
+            Some(c @ '\t') | Some(c @ '\r') => (visible_char(*c), column.0 != column.1),
+            Some(c) => (*c, column.0 != column.1),
+            None => (' ', false),
+        };
+        match segments.last_mut() {
+            Some((text, h)) if *h == highlighted => text.push(c),
+            _ => segments.push((c.to_string(), highlighted)),
+        }
+    }
+    segments
+        .iter()
+        .map(|(text, highlighted)| {
+            if *highlighted {
+                highlight(text).to_string()
+            } else {
+                color(text).to_string()
+            }
+        })
+        .collect()
+}
+
+/// Width of a single column of the side by side diff, or `None` if the unified
+/// diff should be used instead
+fn side_by_side_column_width(gutter: &Gutter) -> Option<usize> {
+    if diff_layout() != DiffLayout::SideBySide {
+        return None;
+    }
+    // `- ` markers in front of both columns and ` │ ` between them
+    let width = (crate::utils::terminal_width().saturating_sub(7) / 2)
+        .saturating_sub(gutter.column_prefix_width());
+    if width >= MIN_SIDE_BY_SIDE_COLUMN_WIDTH {
+        Some(width)
+    } else {
+        None
+    }
+}
+
+/// One side of a row in the side by side diff
+struct Cell<'a> {
+    line: &'a str,
+    number: Option<usize>,
+    ranges: &'a [Range<usize>],
+    /// Show whitespace characters as visible markers
+    visible_whitespace: bool,
+    marker: ColoredString,
+    color: fn(&str) -> ColoredString,
+    highlight: fn(&str) -> ColoredString,
+}
+
+fn write_side_by_side(result: &mut String, blocks: &[Block], width: usize, gutter: &Gutter) {
+    for block in blocks {
+        match block {
+            Block::Unchanged(l) => {
+                let cell = |number| Cell {
+                    line: &l.text,
+                    number,
+                    ranges: &[],
+                    visible_whitespace: false,
+                    marker: " ".normal(),
+                    color: |s| s.dimmed(),
+                    highlight: |s| s.dimmed(),
+                };
+                let (left, right) = (cell(l.left_number), cell(l.right_number));
+                write_row(result, Some(left), Some(right), width, gutter);
+            }
+            Block::Collapsed(lines) => {
+                let prefix = gutter.column_prefix(None);
+                writeln!(
+                    result,
+                    "{}  {}",
+                    prefix,
+                    collapsed_separator(*lines, "line")
+                )
+                .unwrap();
+            }
+            Block::Changed { removed, added } => {
+                // Long lines don't fit into columns, so they are printed across both
+                if is_long_line_change(removed, added) {
+                    write_long_lines(result, removed[0], added[0], gutter);
+                    continue;
+                }
+                for i in 0..removed.len().max(added.len()) {
+                    let left = removed.get(i).map(|l| Cell {
+                        line: &l.text,
+                        number: l.left_number,
+                        ranges: &l.changed_ranges,
+                        visible_whitespace: shows_whitespace(l, added.get(i)),
+                        marker: "-".red(),
+                        color: |s| s.red(),
+                        highlight: |s| s.white().on_red(),
+                    });
+                    let right = added.get(i).map(|r| Cell {
+                        line: &r.text,
+                        number: r.right_number,
+                        ranges: &r.changed_ranges,
+                        visible_whitespace: shows_whitespace(r, removed.get(i)),
+                        marker: "+".green(),
+                        color: |s| s.green(),
+                        highlight: |s| s.black().on_green(),
+                    });
+                    write_row(result, left, right, width, gutter);
+                }
+            }
+            Block::MissingNewline { in_left } => {
+                let cell = Cell {
+                    line: MISSING_NEWLINE,
+                    number: None,
+                    ranges: &[],
+                    visible_whitespace: false,
+                    marker: if *in_left { "-".red() } else { "+".green() },
+                    color: if *in_left { |s| s.red() } else { |s| s.green() },
+                    highlight: |s| s.normal(),
+                };
+                if *in_left {
+                    write_row(result, Some(cell), None, width, gutter);
+                } else {
+                    write_row(result, None, Some(cell), width, gutter);
+                }
+            }
+        }
+    }
+}
+
+/// Write both sides next to each other, wrapping lines that don't fit into the column
+fn write_row(
+    result: &mut String,
+    left: Option<Cell>,
+    right: Option<Cell>,
+    width: usize,
+    gutter: &Gutter,
+) {
+    let wrap_cell = |cell: &Option<Cell>| {
+        cell.as_ref()
+            .map(|cell| wrap(cell.line, cell.ranges, cell.visible_whitespace, width))
+            .unwrap_or_default()
+    };
+    let (left_chunks, right_chunks) = (wrap_cell(&left), wrap_cell(&right));
+
+    for i in 0..left_chunks.len().max(right_chunks.len()) {
+        let render =
+            |cell: &Option<Cell>, chunks: &[Vec<(String, bool)>]| match (cell, chunks.get(i)) {
+                (Some(cell), Some(chunk)) => {
+                    let (number, marker) = if i == 0 {
+                        (cell.number, cell.marker.clone())
+                    } else {
+                        (None, " ".normal())
+                    };
+                    let text = chunk
+                        .iter()
+                        .map(|(text, highlighted)| {
+                            let style = if *highlighted {
+                                cell.highlight
+                            } else {
+                                cell.color
+                            };
+                            style(text).to_string()
+                        })
+                        .collect::<String>();
+                    let len = chunk.iter().map(|(text, _)| text.chars().count()).sum();
+                    let prefix = gutter.column_prefix(number);
+                    (format!("{}{} {}", prefix, marker, text), len)
+                }
+                _ => (format!("{}  ", gutter.column_prefix(None)), 0),
+            };
+
+        let (left_text, left_len) = render(&left, &left_chunks);
+        let (right_text, _) = render(&right, &right_chunks);
+        let row = format!(
+            "{}{} │ {}",
+            left_text,
+            " ".repeat(width - left_len),
+            right_text
+        );
+        writeln!(result, "{}", row.trim_end()).unwrap();
+    }
+}
+
+/// Split a line into chunks of at most `width` characters. Every chunk consists
+/// of segments that are either highlighted or not
+fn wrap(
+    line: &str,
+    ranges: &[Range<usize>],
+    visible_whitespace: bool,
+    width: usize,
+) -> Vec<Vec<(String, bool)>> {
+    let mut chunks: Vec<Vec<(String, bool)>> = vec![vec![]];
+    let mut chunk_len = 0;
+    let line = if visible_whitespace {
+        line
+    } else {
+        without_carriage_return(line)
+    };
+    for (i, c) in line.char_indices() {
+        if chunk_len == width {
+            chunks.push(vec![]);
+            chunk_len = 0;
+        }
+        let highlighted = ranges.iter().any(|range| range.contains(&i));
+        let c = if visible_whitespace {
+            visible_char(c)
+        } else {
+            c
+        };
+        let chunk = chunks.last_mut().unwrap();
+        match chunk.last_mut() {
+            Some((text, h)) if *h == highlighted => text.push(c),
+            _ => chunk.push((c.to_string(), highlighted)),
+        }
+        chunk_len += 1;
+    }
+    chunks
+}
+
+fn highlight<F, H>(
+    line: &str,
+    ranges: &[Range<usize>],
+    visible_whitespace: bool,
+    color: F,
+    highlight: H,
+) -> String
+where
+    F: Fn(&str) -> ColoredString,
+    H: Fn(&str) -> ColoredString,
+{
+    let shown;
+    let (line, ranges) = if visible_whitespace {
+        // Markers are longer than the characters they replace,
+        // so the ranges have to be moved accordingly
+        let offset = |i: usize| show_whitespace(&line[..i]).len();
+        shown = show_whitespace(line);
+        let moved = ranges
+            .iter()
+            .map(|range| offset(range.start)..offset(range.end))
+            .collect::<Ranges>();
+        (shown.as_str(), moved)
+    } else {
+        let line = without_carriage_return(line);
+        let clamped = ranges
+            .iter()
+            .map(|range| range.start.min(line.len())..range.end.min(line.len()))
+            .filter(|range| !range.is_empty())
+            .collect::<Ranges>();
+        (line, clamped)
+    };
+
+    if ranges.is_empty() {
+        return color(line).to_string();
+    }
+
+    let mut result = String::new();
+    let mut pos = 0;
+    for range in &ranges {
+        if pos < range.start {
+            result.push_str(&color(&line[pos..range.start]).to_string());
+        }
+        result.push_str(&highlight(&line[range.clone()]).to_string());
+        pos = range.end;
+    }
+    if pos < line.len() {
+        result.push_str(&color(&line[pos..]).to_string());
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_around_long_line_changes() {
+        let left = "a".repeat(300);
+        let mut right = left.clone();
+        right.replace_range(100..101, "b");
+        right.replace_range(110..111, "b");
+        right.replace_range(295..296, "b");
+        let columns = align_chars(&left, &right);
+
+        // close changes share a window and the end isn't worth collapsing
+        k9_stable::assert_equal!(
+            change_windows(&columns, 5),
+            vec![95..116, 290..columns.len()]
+        );
+        k9_stable::assert_equal!(change_windows(&align_chars(&left, &left), 5).len(), 0);
+    }
+}
@@ -0,0 +1,347 @@
+use crate::debug_tree::Node;
+use crate::sequence_diff::{self, Edit};
+use colored::*;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Values longer than this are cut in the list of changes, the line diff
+/// below it has the full version
+const MAX_VALUE_LENGTH: usize = 60;
+
+/// Only this many changes are listed, the rest are summarized
+const MAX_CHANGES: usize = 10;
+
+/// Difference between two parsed `{:#?}` values at a single place in the tree
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Changed {
+        path: String,
+        left: String,
+        right: String,
+    },
+    Added {
+        path: String,
+        value: String,
+    },
+    Removed {
+        path: String,
+        value: String,
+    },
+}
+
+/// Walk both trees together and collect every place where they are different.
+/// Paths look like field access in Rust, e.g. `.users[3].address.zip`
+pub fn diff(left: &Node, right: &Node) -> Vec<Change> {
+    let mut changes = vec![];
+    diff_into(left, right, "", &mut changes);
+    changes
+}
+
+/// List of changes between two `{:#?}` strings, e.g. `.address.zip: 12345 → 54321`.
+/// Returns `None` if they aren't nested values, since the line diff
+/// would say the same thing.
+pub fn describe(left: &str, right: &str) -> Option<String> {
+    let (left, right) = (Node::parse(left), Node::parse(right));
+    if matches!(left, Node::Atom(_)) || matches!(right, Node::Atom(_)) {
+        return None;
+    }
+
+    let changes = diff(&left, &right);
+    if changes.is_empty()
+        || matches!(changes.as_slice(), [Change::Changed { path, .. }] if path.is_empty())
+    {
+        return None;
+    }
+
+    let mut lines = changes
+        .iter()
+        .take(MAX_CHANGES)
+        .map(|change| match change {
+            Change::Changed { path, left, right } => format!(
+                "  {}: {} → {}",
+                path.yellow(),
+                truncate(left).red(),
+                truncate(right).green()
+            ),
+            Change::Added { path, value } => {
+                format!("  {}: added {}", path.yellow(), truncate(value).green())
+            }
+            Change::Removed { path, value } => {
+                format!("  {}: removed {}", path.yellow(), truncate(value).red())
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if changes.len() > MAX_CHANGES {
+        lines.push(format!("  ... and {} more", changes.len() - MAX_CHANGES));
+    }
+
+    Some(format!("\nDifferences:\n{}\n", lines.join("\n")))
+}
+
+fn truncate(value: &str) -> String {
+    if value.chars().count() > MAX_VALUE_LENGTH {
+        let truncated = value.chars().take(MAX_VALUE_LENGTH - 1).collect::<String>();
+        format!("{}…", truncated)
+    } else {
+        value.to_string()
+    }
+}
+
+fn diff_into(left: &Node, right: &Node, path: &str, changes: &mut Vec<Change>) {
+    if left == right {
+        return;
+    }
+
+    match (left, right) {
+        (
+            Node::Struct {
+                name: left_name,
+                fields: left_fields,
+            },
+            Node::Struct {
+                name: right_name,
+                fields: right_fields,
+            },
+        ) if left_name == right_name => diff_entries(
+            left_fields,
+            right_fields,
+            |field| format!("{}.{}", path, field),
+            changes,
+        ),
+        (
+            Node::Tuple {
+                name: left_name,
+                items: left_items,
+            },
+            Node::Tuple {
+                name: right_name,
+                items: right_items,
+            },
+        ) if left_name == right_name => {
+            // `Some(x)` and newtypes are looked through, so the path reads
+            // `.address.zip` rather than `.address.0.zip`
+            if !left_name.is_empty() && left_items.len() == 1 && right_items.len() == 1 {
+                return diff_into(&left_items[0], &right_items[0], path, changes);
+            }
+            for i in 0..left_items.len().max(right_items.len()) {
+                let item_path = format!("{}.{}", path, i);
+                diff_optional(left_items.get(i), right_items.get(i), item_path, changes);
+            }
+        }
+        (Node::Seq(left_items), Node::Seq(right_items)) => {
+            diff_seq(left_items, right_items, path, changes)
+        }
+        (Node::Map(left_entries), Node::Map(right_entries)) => diff_entries(
+            left_entries,
+            right_entries,
+            |key| format!("{}[{}]", path, key.render_compact()),
+            changes,
+        ),
+        (Node::Set(left_items), Node::Set(right_items)) => {
+            let left_set = left_items.iter().collect::<HashSet<_>>();
+            let right_set = right_items.iter().collect::<HashSet<_>>();
+            for item in left_items.iter().filter(|item| !right_set.contains(item)) {
+                changes.push(Change::Removed {
+                    path: path.to_string(),
+                    value: item.render_compact(),
+                });
+            }
+            for item in right_items.iter().filter(|item| !left_set.contains(item)) {
+                changes.push(Change::Added {
+                    path: path.to_string(),
+                    value: item.render_compact(),
+                });
+            }
+        }
+        _ => changes.push(Change::Changed {
+            path: path.to_string(),
+            left: left.render_compact(),
+            right: right.render_compact(),
+        }),
+    }
+}
+
+fn diff_optional(
+    left: Option<&Node>,
+    right: Option<&Node>,
+    path: String,
+    changes: &mut Vec<Change>,
+) {
+    match (left, right) {
+        (Some(left), Some(right)) => diff_into(left, right, &path, changes),
+        (Some(left), None) => changes.push(Change::Removed {
+            path,
+            value: left.render_compact(),
+        }),
+        (None, Some(right)) => changes.push(Change::Added {
+            path,
+            value: right.render_compact(),
+        }),
+        (None, None) => {}
+    }
+}
+
+/// Struct fields and map entries are matched by their keys, not by position
+fn diff_entries<K: Eq + Hash>(
+    left: &[(K, Node)],
+    right: &[(K, Node)],
+    key_path: impl Fn(&K) -> String,
+    changes: &mut Vec<Change>,
+) {
+    // Looked up by key, so large maps don't take quadratic time
+    let left_map = left.iter().map(|(k, v)| (k, v)).collect::<HashMap<_, _>>();
+    let right_map = right.iter().map(|(k, v)| (k, v)).collect::<HashMap<_, _>>();
+
+    for (key, value) in left {
+        diff_optional(
+            Some(value),
+            right_map.get(key).copied(),
+            key_path(key),
+            changes,
+        );
+    }
+    for (key, value) in right {
+        if !left_map.contains_key(key) {
+            diff_optional(None, Some(value), key_path(key), changes);
+        }
+    }
+}
+
+/// Sequence items are aligned first, so an item inserted in the middle doesn't
+/// show up as a change of every item after it. Removed items that were replaced
+/// by added ones are compared with each other.
+fn diff_seq(left: &[Node], right: &[Node], path: &str, changes: &mut Vec<Change>) {
+    let mut removed = vec![];
+    let mut added = vec![];
+    let (mut l, mut r) = (0, 0);
+
+    let mut flush = |removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        for i in 0..removed.len().max(added.len()) {
+            let (left_index, right_index) = (removed.get(i), added.get(i));
+            let index = left_index.or(right_index).unwrap();
+            diff_optional(
+                left_index.map(|l| &left[*l]),
+                right_index.map(|r| &right[*r]),
+                format!("{}[{}]", path, index),
+                changes,
+            );
+        }
+        removed.clear();
+        added.clear();
+    };
+
+    for item in sequence_diff::slice(left, right) {
+        match item {
+            Edit::Left(_) => {
+                if !added.is_empty() {
+                    flush(&mut removed, &mut added);
+                }
+                removed.push(l);
+                l += 1;
+            }
+            Edit::Right(_) => {
+                added.push(r);
+                r += 1;
+            }
+            Edit::Both(..) => {
+                flush(&mut removed, &mut added);
+                l += 1;
+                r += 1;
+            }
+        }
+    }
+    flush(&mut removed, &mut added);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[derive(Debug, Clone)]
+    #[allow(dead_code)]
+    struct Address {
+        street: &'static str,
+        zip: u32,
+    }
+
+    #[derive(Debug, Clone)]
+    #[allow(dead_code)]
+    struct User {
+        name: &'static str,
+        address: Option<Address>,
+        roles: BTreeSet<&'static str>,
+    }
+
+    #[derive(Debug, Clone)]
+    #[allow(dead_code)]
+    struct Team {
+        users: Vec<User>,
+        scores: BTreeMap<&'static str, u32>,
+    }
+
+    fn changes<T: std::fmt::Debug>(left: &T, right: &T) -> Vec<String> {
+        diff(
+            &Node::parse(&format!("{:#?}", left)),
+            &Node::parse(&format!("{:#?}", right)),
+        )
+        .into_iter()
+        .map(|change| match change {
+            Change::Changed { path, left, right } => format!("{}: {} → {}", path, left, right),
+            Change::Added { path, value } => format!("{}: added {}", path, value),
+            Change::Removed { path, value } => format!("{}: removed {}", path, value),
+        })
+        .collect()
+    }
+
+    fn user(name: &'static str, zip: u32) -> User {
+        User {
+            name,
+            address: Some(Address {
+                street: "Main st",
+                zip,
+            }),
+            roles: vec!["member"].into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn field_paths() {
+        let left = Team {
+            users: vec![user("a", 1), user("b", 2), user("c", 3)],
+            scores: vec![("a", 1), ("b", 2)].into_iter().collect(),
+        };
+        let mut right = left.clone();
+        right.users[2].address.as_mut().unwrap().zip = 54321;
+        right.users[1].address = None;
+        right.users[0].roles.insert("admin");
+        right.scores.remove("a");
+        right.scores.insert("b", 5);
+
+        k9_stable::assert_equal!(
+            changes(&left, &right),
+            vec![
+                r#".users[0].roles: added "admin""#,
+                r#".users[1].address: Some(Address { street: "Main st", zip: 2 }) → None"#,
+                ".users[2].address.zip: 3 → 54321",
+                r#".scores["a"]: removed 1"#,
+                r#".scores["b"]: 2 → 5"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn aligns_sequence_items() {
+        let left = vec![user("a", 1), user("b", 2)];
+        let right = vec![user("new", 0), user("a", 1), user("b", 3)];
+
+        k9_stable::assert_equal!(
+            changes(&left, &right),
+            vec![
+                r#"[0]: added User { name: "new", address: Some(Address { street: "Main st", zip: 0 }), roles: {"member"} }"#,
+                "[1].address.zip: 2 → 3",
+            ]
+        );
+    }
+}
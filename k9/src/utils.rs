@@ -2,15 +2,34 @@ pub fn add_linebreaks(s: &str) -> String {
     format!("\n{}\n", s)
 }
 
-pub fn terminal_separator_line() -> String {
+pub fn terminal_width() -> usize {
     let width_override = crate::config::terminal_width_override();
-    let width = if width_override != 0 {
+    if width_override != 0 {
         width_override
     } else if let Some((width, _)) = terminal_size::terminal_size() {
         width.0 as usize
     } else {
         100 // default width if we can't determine terminal width
-    };
+    }
+}
+
+pub fn terminal_separator_line() -> String {
+    "━".repeat(terminal_width())
+}
 
-    "━".repeat(width)
+/// Remove ANSI escape sequences (colors), e.g. before writing colored text to a file
+pub fn strip_ansi_escapes(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // `ESC [`, parameters and a final byte in `@`..=`~`
+            if chars.next() == Some('[') {
+                chars.by_ref().find(|c| ('@'..='~').contains(c));
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
 }
@@ -0,0 +1,128 @@
+use k9::alloc::{count_allocations, AllocationStats, CountingAllocator};
+use k9::{assert_equal, assert_max_allocations, assert_no_alloc};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator::system();
+
+fn setup_test_env() {
+    k9::config::set_panic(false);
+    k9::config::set_terminal_with_override(100);
+    colored::control::set_override(true);
+}
+
+fn assertion_message(m: Option<k9::assertions::Assertion>) -> String {
+    String::from_utf8(
+        strip_ansi_escapes::strip(m.expect("Assertion must fail").get_failure_message()).unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn counts_allocations() {
+    let (_, stats) = count_allocations(|| {
+        let mut v: Vec<u64> = Vec::with_capacity(2);
+        v.push(1);
+        drop(v);
+        let _a = Box::new([0u8; 100]);
+        let _b = Box::new([0u8; 50]);
+    });
+
+    assert_equal!(
+        stats,
+        AllocationStats {
+            allocations: 3,
+            bytes_allocated: 166,
+            peak_bytes: 150,
+        }
+    );
+}
+
+#[test]
+fn ignores_other_threads() {
+    let (_, stats) = count_allocations(|| {
+        std::thread::scope(|s| {
+            s.spawn(|| vec![0u8; 1000]);
+        });
+    });
+
+    assert!(stats.bytes_allocated < 1000);
+}
+
+#[test]
+fn nested_counting() {
+    let (inner, outer) = count_allocations(|| {
+        let _a = Box::new(1u32);
+        count_allocations(|| Box::new(1u64)).1
+    });
+
+    assert_equal!(inner.allocations, 1);
+    assert_equal!(inner.bytes_allocated, 8);
+    assert_equal!(outer.allocations, 2);
+    assert_equal!(outer.bytes_allocated, 12);
+}
+
+#[test]
+fn test_assert_max_allocations() {
+    setup_test_env();
+
+    assert!(assert_max_allocations!(1, || vec![1, 2, 3]).is_none());
+    assert!(assert_max_allocations!(0, || 1 + 1).is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_max_allocations!(
+            2,
+            || vec![vec![1u8; 10], vec![2u8; 20], vec![3u8; 30]],
+            "Hot path must stay within the allocation budget"
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_max_allocations!(2, || vec![vec![1u8; 10], vec![2u8; 20], vec![3u8; 30]], "Hot path must stay within the allocation budget");
+
+Hot path must stay within the allocation budget
+
+Expected closure to allocate at most 2 times,
+but it allocated 4 times
+
+Allocations:     4
+Bytes allocated: 132
+Peak memory:     132 bytes
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn test_assert_no_alloc() {
+    setup_test_env();
+
+    let numbers = [1, 2, 3];
+    assert!(assert_no_alloc!(|| numbers.iter().sum::<i32>()).is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_no_alloc!(|| {
+            let mut s = String::with_capacity(8);
+            s.push_str("hello world");
+            s
+        })),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_no_alloc!(|| { let mut s = String::with_capacity(8); s.push_str("hello world"); s });
+
+Assertion Failure!
+
+Expected closure not to allocate,
+but it allocated 2 times
+
+Allocations:     2
+Bytes allocated: 24
+Peak memory:     16 bytes
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
@@ -0,0 +1,122 @@
+use crate::assertion_message;
+use k9::{assert_all, assert_equal};
+
+#[derive(Debug, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+fn test_assert_all() {
+    super::setup_test_env();
+
+    assert!(assert_all!(vec![1, 2, 3], |x| *x > 0).is_none());
+    assert!(assert_all!(Vec::<i32>::new(), |x| *x > 0).is_none());
+    assert!(assert_all!(&[1, 2, 3], |x| **x > 0, "all positive").is_none());
+    assert!(assert_all!(1..10, |x| assert_equal!(*x / 10, 0)).is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_all!(vec![1, -2, 3, -4, 5], |x| *x > 0)),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_all!(vec![1, -2, 3, -4, 5], |x| *x > 0);
+
+Assertion Failure!
+
+
+Expected all items of `Collection` to satisfy `Predicate`,
+but 2 of 5 items didn't:
+
+[1] -2
+[3] -4
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn nested_assertion() {
+    super::setup_test_env();
+
+    let points = vec![
+        Point { x: 1, y: 1 },
+        Point { x: 2, y: 3 },
+        Point { x: 3, y: 3 },
+    ];
+
+    k9_stable::snapshot!(
+        assertion_message(assert_all!(
+            &points,
+            |p| assert_equal!(p.x, p.y, "point should be on the diagonal"),
+            "Every point must be on the diagonal"
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_all!(&points, |p| assert_equal!(p.x, p.y, "point should be on the diagonal"), "Every point must be on the diagonal");
+
+Every point must be on the diagonal
+
+
+Expected all items of `Collection` to satisfy `Predicate`,
+but 1 of 3 items didn't:
+
+[1] Point {
+        x: 2,
+        y: 3,
+    }
+    assert_equal!(p.x, p.y, "point should be on the diagonal");
+    point should be on the diagonal
+    Expected `Left` to equal `Right`:
+
+    - 2
+    + 3
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn assertions_inside_predicate() {
+    super::setup_test_env();
+
+    // assertions that aren't returned from the predicate still fail the item
+    k9_stable::snapshot!(
+        assertion_message(assert_all!(vec![1, 2, 1], |x| {
+            assert_equal!(*x, 1);
+            true
+        })),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_all!(vec![1, 2, 1], |x| { assert_equal!(*x, 1); true });
+
+Assertion Failure!
+
+
+Expected all items of `Collection` to satisfy `Predicate`,
+but 1 of 3 items didn't:
+
+[1] 2
+    assert_equal!(*x, 1);
+    Expected `Left` to equal `Right`:
+
+    - 2
+    + 1
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+    assert!(assert_all!(vec![1, 1], |x| {
+        assert_equal!(*x, 1);
+        true
+    })
+    .is_none());
+}
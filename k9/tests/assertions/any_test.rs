@@ -0,0 +1,96 @@
+use crate::assertion_message;
+use k9::{assert_any, assert_greater_than};
+
+#[test]
+fn test_assert_any() {
+    super::setup_test_env();
+
+    assert!(assert_any!(vec![1, 2, 3], |x| *x == 3).is_none());
+    assert!(assert_any!(vec![1, 2, 3], |x| *x == 3, "contains {}", 3).is_none());
+    assert!(assert_any!(vec![1, 2, 3], |x| assert_greater_than!(*x, 2)).is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_any!(0..25, |x| *x > 100)),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_any!(0..25, |x| *x > 100);
+
+Assertion Failure!
+
+
+Expected at least one item of `Collection` to satisfy `Predicate`,
+but none of 25 items did. Inspected items:
+
+[0] 0
+[1] 1
+[2] 2
+[3] 3
+[4] 4
+[5] 5
+[6] 6
+[7] 7
+[8] 8
+[9] 9
+... and 15 more
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+    k9_stable::snapshot!(
+        assertion_message(assert_any!(Vec::<u8>::new(), |x| *x > 100)),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_any!(Vec::<u8>::new(), |x| *x > 100);
+
+Assertion Failure!
+
+
+Expected at least one item of `Collection` to satisfy `Predicate`,
+but the collection is empty
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+}
+
+#[test]
+fn nested_assertion() {
+    super::setup_test_env();
+
+    k9_stable::snapshot!(
+        assertion_message(assert_any!(vec![1, 2], |x| assert_greater_than!(*x, 2))),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_any!(vec![1, 2], |x| assert_greater_than!(*x, 2));
+
+Assertion Failure!
+
+
+Expected at least one item of `Collection` to satisfy `Predicate`,
+but none of 2 items did. Inspected items:
+
+[0] 1
+    assert_greater_than!(*x, 2);
+    Expected Left value to be greater than Right value
+
+    Left value:  1
+    Right value: 2
+
+[1] 2
+    assert_greater_than!(*x, 2);
+    Expected Left value to be greater than Right value,
+    but they were equal
+
+    Left value:  2
+    Right value: 2
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+}
@@ -0,0 +1,183 @@
+use crate::assertion_message;
+use k9::{assert_no_message, assert_receives};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Started { id: u32 },
+    Progress { id: u32, percent: u8 },
+    Finished { id: u32 },
+}
+
+const TIMEOUT: Duration = Duration::from_millis(10);
+
+#[test]
+fn receives() {
+    super::setup_test_env();
+
+    let (tx, rx) = channel();
+    std::thread::spawn(move || {
+        tx.send(Event::Started { id: 1 }).unwrap();
+        tx.send(Event::Finished { id: 1 }).unwrap();
+    });
+    assert!(assert_receives!(rx, Event::Started { id: 1 }, Duration::from_secs(5)).is_none());
+    assert!(assert_receives!(&rx, Event::Finished { id: 1 }, Duration::from_secs(5)).is_none());
+
+    let (tx, rx) = channel();
+    tx.send(Event::Started { id: 1 }).unwrap();
+    tx.send(Event::Progress { id: 1, percent: 50 }).unwrap();
+    tx.send(Event::Finished { id: 1 }).unwrap();
+    k9_stable::snapshot!(
+        assertion_message(assert_receives!(rx, Event::Started { id: 2 }, TIMEOUT)),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_receives!(rx, Event::Started { id: 2 }, TIMEOUT);
+
+Assertion Failure!
+
+Expected `Receiver` to receive `Expected` within 10ms,
+but received a different message.
+
+`Received` - `Expected`:
+
+  Started {
+-     id: 1,
++     id: 2,
+  }
+
+Remaining queued messages (2):
+[0] Progress {
+    id: 1,
+    percent: 50,
+}
+[1] Finished {
+    id: 1,
+}
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+    k9_stable::snapshot!(
+        assertion_message(assert_receives!(
+            rx,
+            Event::Started { id: 2 },
+            TIMEOUT,
+            "Worker must start the second job"
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_receives!(rx, Event::Started { id: 2 }, TIMEOUT, "Worker must start the second job");
+
+Worker must start the second job
+
+Expected `Receiver` to receive `Expected` within 10ms,
+but nothing arrived before the deadline
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+
+    drop(tx);
+    k9_stable::snapshot!(
+        assertion_message(assert_receives!(rx, Event::Started { id: 2 }, TIMEOUT)),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_receives!(rx, Event::Started { id: 2 }, TIMEOUT);
+
+Assertion Failure!
+
+Expected `Receiver` to receive `Expected` within 10ms,
+but the channel was disconnected
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+}
+
+#[test]
+fn no_message() {
+    super::setup_test_env();
+
+    let (tx, rx) = channel();
+    assert!(assert_no_message!(rx, TIMEOUT).is_none());
+
+    tx.send(Event::Progress { id: 3, percent: 10 }).unwrap();
+    tx.send(Event::Finished { id: 3 }).unwrap();
+    k9_stable::snapshot!(
+        assertion_message(assert_no_message!(rx, TIMEOUT)),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_no_message!(rx, TIMEOUT);
+
+Assertion Failure!
+
+Expected `Receiver` to receive no messages within 10ms,
+but received:
+Progress {
+    id: 3,
+    percent: 10,
+}
+
+Remaining queued messages (1):
+[0] Finished {
+    id: 3,
+}
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+
+    drop(tx);
+    assert!(assert_no_message!(rx, TIMEOUT, "disconnected").is_none());
+}
+
+#[test]
+fn endless_producer() {
+    super::setup_test_env();
+
+    let (tx, rx) = channel();
+    let producer = std::thread::spawn(move || {
+        let mut id = 0;
+        // stops once the receiver is dropped
+        while tx.send(Event::Started { id }).is_ok() {
+            id += 1;
+        }
+    });
+    // make sure there's more in the queue than the message can show
+    while assert_no_message!(rx, TIMEOUT).is_none() {}
+    std::thread::sleep(TIMEOUT);
+
+    let message = assertion_message(assert_no_message!(rx, TIMEOUT));
+    assert!(message.contains("[19] Started"), "{}", message);
+    assert!(!message.contains("[20] Started"), "{}", message);
+    assert!(message.contains("… and "), "{}", message);
+
+    drop(rx);
+    producer.join().unwrap();
+}
+
+#[cfg(feature = "crossbeam")]
+#[test]
+fn crossbeam() {
+    super::setup_test_env();
+
+    let (tx, rx) = crossbeam_channel::unbounded();
+    tx.send(1).unwrap();
+    assert!(assert_receives!(rx, 1, TIMEOUT).is_none());
+    assert!(assert_no_message!(rx, TIMEOUT).is_none());
+
+    tx.send(2).unwrap();
+    tx.send(3).unwrap();
+    let message = assertion_message(assert_receives!(rx, 1, TIMEOUT));
+    assert!(message.contains("but received a different message."));
+    assert!(message.contains("Remaining queued messages (1):\n[0] 3"));
+}
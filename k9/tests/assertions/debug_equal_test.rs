@@ -0,0 +1,120 @@
+use crate::assertion_message;
+use k9::assert_debug_equal;
+
+// Intentionally doesn't implement `PartialEq`
+#[allow(dead_code)]
+#[derive(Debug)]
+struct Token {
+    kind: &'static str,
+    span: (usize, usize),
+}
+
+#[test]
+fn test_assert_debug_equal() {
+    super::setup_test_env();
+
+    let token = Token {
+        kind: "ident",
+        span: (0, 5),
+    };
+
+    assert!(assert_debug_equal!(token, r#"Token { kind: "ident", span: (0, 5) }"#).is_none());
+    assert!(assert_debug_equal!(
+        token,
+        r#"
+Token {
+    kind: "ident",
+    span: (
+        0,
+        5,
+    ),
+}
+"#
+    )
+    .is_none());
+    assert!(assert_debug_equal!(Some(1), "Some(1)", "with description {}", 1).is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_debug_equal!(
+            token,
+            r#"Token { kind: "ident", span: (0, 4) }"#
+        )),
+        r##"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_debug_equal!(token, r#"Token { kind: "ident", span: (0, 4) }"#);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+Differences:
+  .span.1: 5 → 4
+
+- Token { kind: "ident", span: (0, 5) }
++ Token { kind: "ident", span: (0, 4) }
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"##
+    );
+}
+
+#[test]
+fn multiline() {
+    super::setup_test_env();
+
+    let token = Token {
+        kind: "ident",
+        span: (0, 5),
+    };
+
+    k9_stable::snapshot!(
+        assertion_message(assert_debug_equal!(
+            token,
+            r#"
+Token {
+    kind: "literal",
+    span: (
+        0,
+        5,
+    ),
+}
+"#
+        )),
+        r##"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_debug_equal!(token, r#"
+Token {
+    kind: "literal",
+    span: (
+        0,
+        5,
+    ),
+}
+"#);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+Differences:
+  .kind: "ident" → "literal"
+
+  Token {
+-     kind: "ident",
++     kind: "literal",
+      span: (
+          0,
+          5,
+      ),
+  }
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"##
+    );
+}
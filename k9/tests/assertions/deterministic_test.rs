@@ -0,0 +1,68 @@
+use crate::assertion_message;
+use k9::assert_deterministic;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn test_assert_deterministic() {
+    super::setup_test_env();
+
+    assert!(assert_deterministic!(5, || (1..5).collect::<Vec<_>>()).is_none());
+
+    let mut run = 0;
+    k9_stable::snapshot!(
+        assertion_message(assert_deterministic!(
+            5,
+            || {
+                run += 1;
+                let mut map = BTreeMap::new();
+                map.insert("a", 1);
+                map.insert("b", if run >= 3 { 3 } else { 2 });
+                map
+            },
+            "Config must not depend on the run"
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_deterministic!(5, ||
+{
+    run += 1; let mut map = BTreeMap::new(); map.insert("a", 1);
+    map.insert("b", if run >= 3 { 3 } else { 2 }); map
+}, "Config must not depend on the run");
+
+Config must not depend on the run
+
+Expected closure to return equal results on all 5 runs,
+but run 3 returned a different result than run 1
+
+Expected `Left` to equal `Right`:
+
+Differences:
+  ["b"]: 2 → 3
+
+  {
+      "a": 1,
+-     "b": 2,
++     "b": 3,
+  }
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn threaded() {
+    super::setup_test_env();
+
+    assert!(assert_deterministic!(8, threaded, || (1..100).sum::<u32>()).is_none());
+
+    // every run gets a different number
+    let counter = AtomicUsize::new(0);
+    let message = assertion_message(assert_deterministic!(4, threaded, || {
+        counter.fetch_add(1, Ordering::SeqCst)
+    }));
+    assert!(message.contains("on all 4 runs on separate threads,\nbut run 2 returned"));
+}
@@ -0,0 +1,64 @@
+use crate::assertion_message;
+use k9::assert_display_equal;
+use std::fmt;
+
+struct Table(Vec<(&'static str, u32)>);
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in &self.0 {
+            writeln!(f, "{:<6}|{:>4}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_assert_display_equal() {
+    super::setup_test_env();
+
+    assert!(assert_display_equal!(42, "42").is_none());
+    assert!(assert_display_equal!("hello", "hello", "with description").is_none());
+    assert!(assert_display_equal!(
+        Table(vec![("a", 1), ("b", 22)]),
+        "
+a     |   1
+b     |  22
+"
+    )
+    .is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_display_equal!(
+            Table(vec![("a", 1), ("b", 22), ("c", 333)]),
+            "
+a     |   1
+b     |  20
+c     | 333
+"
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_display_equal!(Table(vec![("a", 1), ("b", 22), ("c", 333)]), "
+a     |   1
+b     |  20
+c     | 333
+");
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+  a     |   1
+- b     |  22
++ b     |  20
+  c     | 333
+  
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
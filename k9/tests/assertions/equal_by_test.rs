@@ -0,0 +1,134 @@
+use crate::assertion_message;
+use k9::{assert_equal_by, assert_equal_by_key};
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Order {
+    id: (u32, &'static str),
+    items: Vec<&'static str>,
+    total: f64,
+}
+
+fn order() -> Order {
+    Order {
+        id: (7, "eu"),
+        items: vec!["apple", "pear"],
+        total: 3.5,
+    }
+}
+
+#[test]
+fn equal_by() {
+    super::setup_test_env();
+
+    assert!(assert_equal_by!(0.1 + 0.2, 0.3, |a: &f64, b: &f64| (a - b).abs() < 1e-9).is_none());
+    assert!(assert_equal_by!(
+        order(),
+        Order {
+            total: 3.49,
+            ..order()
+        },
+        |a: &Order, b: &Order| a.id == b.id && (a.total - b.total).abs() < 0.1
+    )
+    .is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_equal_by!(
+            order(),
+            Order {
+                total: 4.0,
+                ..order()
+            },
+            |a: &Order, b: &Order| (a.total - b.total).abs() < 0.1,
+            "Totals should match up to a rounding error"
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal_by!(order(), Order { total: 4.0, ..order() }, |a: &Order, b: &Order| (a.total - b.total).abs() < 0.1, "Totals should match up to a rounding error");
+
+Totals should match up to a rounding error
+
+
+Expected `Left` to equal `Right` by `Comparator`:
+
+Differences:
+  .total: 3.5 → 4.0
+
+  ⋯ 6 unchanged lines ⋯
+          "apple",
+          "pear",
+      ],
+-     total: 3.5,
++     total: 4.0,
+  }
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn equal_by_key() {
+    super::setup_test_env();
+
+    assert!(assert_equal_by_key!(
+        order(),
+        Order {
+            items: vec![],
+            ..order()
+        },
+        |order: &Order| order.id
+    )
+    .is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_equal_by_key!(
+            order(),
+            Order {
+                id: (8, "eu"),
+                total: 4.0,
+                ..order()
+            },
+            |order: &Order| order.id
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal_by_key!(order(), Order { id: (8, "eu"), total: 4.0, ..order() }, |order: &Order| order.id);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right` by `Key`:
+
+Left key  │ Right key
+(         │ (
+    7,    │     8,
+    "eu", │     "eu",
+)         │ )
+
+Differences:
+  .id.0: 7 → 8
+  .total: 3.5 → 4.0
+
+  Order {
+      id: (
+-         7,
++         8,
+          "eu",
+      ),
+      items: [
+          "apple",
+          "pear",
+      ],
+-     total: 3.5,
++     total: 4.0,
+  }
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
@@ -35,6 +35,8 @@ Expected `Left` to equal `Right`:
     assert!(assert_equal!(123, 123, "Expected two integers to be the same").is_none());
 }
 
+// `serde_json` implements `PartialEq<Value>` for integers, which makes `parse` ambiguous
+#[cfg(not(feature = "serde_json"))]
 #[test]
 fn type_inference() {
     assert_eq!(1, "1".parse().unwrap());
@@ -83,6 +85,10 @@ Assertion Failure!
 
 Expected `Left` to equal `Right`:
 
+Differences:
+  .c.1: 4 → 9
+  .d: Some(()) → None
+
   X {
       a: "test",
       b: 4,
@@ -115,18 +121,22 @@ Assertion Failure!
 
 Expected `\u{1b}[31mLeft\u{1b}[0m` to equal `\u{1b}[32mRight\u{1b}[0m`:
 
+Differences:
+  \u{1b}[33m.c.1\u{1b}[0m: \u{1b}[31m4\u{1b}[0m → \u{1b}[32m9\u{1b}[0m
+  \u{1b}[33m.d\u{1b}[0m: \u{1b}[31mSome(())\u{1b}[0m → \u{1b}[32mNone\u{1b}[0m
+
   \u{1b}[2mX {\u{1b}[0m
   \u{1b}[2m    a: "test",\u{1b}[0m
   \u{1b}[2m    b: 4,\u{1b}[0m
   \u{1b}[2m    c: (\u{1b}[0m
   \u{1b}[2m        "test2",\u{1b}[0m
-\u{1b}[31m-\u{1b}[0m \u{1b}[31m        4,\u{1b}[0m
-\u{1b}[32m+\u{1b}[0m \u{1b}[32m        9,\u{1b}[0m
+\u{1b}[31m-\u{1b}[0m \u{1b}[31m        \u{1b}[0m\u{1b}[41;37m4\u{1b}[0m\u{1b}[31m,\u{1b}[0m
+\u{1b}[32m+\u{1b}[0m \u{1b}[32m        \u{1b}[0m\u{1b}[42;30m9\u{1b}[0m\u{1b}[32m,\u{1b}[0m
   \u{1b}[2m    ),\u{1b}[0m
-\u{1b}[31m-\u{1b}[0m \u{1b}[31m    d: Some(\u{1b}[0m
+\u{1b}[31m-\u{1b}[0m \u{1b}[31m    d: \u{1b}[0m\u{1b}[41;37mSome(\u{1b}[0m
 \u{1b}[31m-\u{1b}[0m \u{1b}[31m        (),\u{1b}[0m
 \u{1b}[31m-\u{1b}[0m \u{1b}[31m    ),\u{1b}[0m
-\u{1b}[32m+\u{1b}[0m \u{1b}[32m    d: None,\u{1b}[0m
+\u{1b}[32m+\u{1b}[0m \u{1b}[32m    d: \u{1b}[0m\u{1b}[42;30mNone,\u{1b}[0m
   \u{1b}[2m}\u{1b}[0m
 
 \u{1b}[2m━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\u{1b}[0m
@@ -153,6 +163,9 @@ Expected [1, 2, 3] to equal [1, 2, 99]
 
 Expected `Left` to equal `Right`:
 
+Differences:
+  [2]: 3 → 99
+
   [
       1,
       2,
@@ -199,3 +212,215 @@ Expected `Left` to equal `Right`:
 "
     );
 }
+
+#[test]
+fn highlights_changed_words() {
+    use colored::*;
+    super::setup_test_env();
+
+    let err = assert_equal!(
+        ("221B Baker Street", "London", 12345),
+        ("221B Baker Street", "London", 12346)
+    )
+    .expect("must fail")
+    .get_failure_message();
+
+    assert!(err.contains(&format!(
+        "{}{}{}",
+        "    ".red(),
+        "12345".white().on_red(),
+        ",".red()
+    )));
+    assert!(err.contains(&format!(
+        "{}{}{}",
+        "    ".green(),
+        "12346".black().on_green(),
+        ",".green()
+    )));
+    // lines that are not changed aren't highlighted
+    assert!(!err.contains(&"London".white().on_red().to_string()));
+}
+
+#[test]
+fn collapses_unchanged_lines() {
+    super::setup_test_env();
+
+    let left = (1..=2000).collect::<Vec<_>>();
+    let mut right = left.clone();
+    right[1500] = 0;
+
+    k9_stable::snapshot!(
+        assertion_message(assert_equal!(left, right)),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal!(left, right);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+Differences:
+  [1500]: 1501 → 0
+
+  ⋯ 1,498 unchanged lines ⋯
+      1498,
+      1499,
+      1500,
+-     1501,
++     0,
+      1502,
+      1503,
+      1504,
+  ⋯ 497 unchanged lines ⋯
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+}
+
+#[test]
+fn lists_structural_differences() {
+    super::setup_test_env();
+
+    #[derive(PartialEq, Debug, Clone)]
+    struct Address {
+        street: String,
+        zip: u32,
+    }
+
+    #[derive(PartialEq, Debug, Clone)]
+    struct User {
+        name: String,
+        address: Option<Address>,
+    }
+
+    let users = (0..5)
+        .map(|i| User {
+            name: format!("user {}", i),
+            address: Some(Address {
+                street: "Main st".to_string(),
+                zip: 12345,
+            }),
+        })
+        .collect::<Vec<_>>();
+    let mut changed = users.clone();
+    changed[3].address.as_mut().unwrap().zip = 54321;
+
+    let message = assertion_message(assert_equal!(users, changed));
+    k9_stable::snapshot!(
+        message,
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal!(users, changed);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+Differences:
+  [3].address.zip: 12345 → 54321
+
+  ⋯ 30 unchanged lines ⋯
+          address: Some(
+              Address {
+                  street: "Main st",
+-                 zip: 12345,
++                 zip: 54321,
+              },
+          ),
+      },
+  ⋯ 10 unchanged lines ⋯
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn whitespace_only_changes() {
+    super::setup_test_env();
+
+    let left = "fn main() {\n    let x = 1;\n\tprintln!(\"{}\", x);\r\n}\n";
+    let right = "fn main() {\n    let x = 1; \n    println!(\"{}\", x);\n}";
+
+    let message = assertion_message(assert_equal!(
+        MultilineString::new(left),
+        MultilineString::new(right)
+    ));
+    k9_stable::snapshot!(
+        message,
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal!(MultilineString::new(left), MultilineString::new(right));
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+Difference is whitespace only (· space, → tab, ␍ carriage return)
+
+  fn main() {
+- ····let·x·=·1;
+- →println!("{}",·x);␍
++ ····let·x·=·1;·
++ ····println!("{}",·x);
+  }
++ ⏎ missing newline at end
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn long_single_lines() {
+    super::setup_test_env();
+
+    let users = (1..=8)
+        .map(|id| format!(r#"{{"id":{},"name":"user{}","active":true}}"#, id, id))
+        .collect::<Vec<_>>();
+    let mut changed = users.clone();
+    changed[1] = changed[1].replace("true", "false");
+    changed[7] = changed[7].replace("user8", "admin");
+    let left = format!(r#"{{"users":[{}]}}"#, users.join(","));
+    let right = format!(r#"{{"users":[{}]}}"#, changed.join(","));
+
+    let message = assertion_message(assert_equal!(
+        MultilineString::new(&left),
+        MultilineString::new(&right)
+    ));
+    k9_stable::snapshot!(
+        message,
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal!(MultilineString::new(&left), MultilineString::new(&right));
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+  ⋯ 40 unchanged characters ⋯
+- ":true},{"id":2,"name":"user2","active":tru    e},{"id":3,"name":"user3","active":true}
++ ":true},{"id":2,"name":"user2","active":   false},{"id":3,"name":"user3","active":true}
+                                          ^^^^^^^
+  ⋯ 129 unchanged characters ⋯
+- :"user7","active":true},{"id":8,"name":"user8     ","active":true}]}
++ :"user7","active":true},{"id":8,"name":"     admin","active":true}]}
+                                          ^^^^^^^^^^
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
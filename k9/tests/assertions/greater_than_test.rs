@@ -9,7 +9,7 @@ fn test_assert_greater_than() -> Result<()> {
     assert!(assert_greater_than!(1, 1).is_some());
     assert!(assert_greater_than!(0, 1).is_some());
     assert!(assert_greater_than!("234", "cde").is_some());
-    assert!(assert_greater_than!(std::f64::NAN, 1.0f64).is_some());
+    assert!(assert_greater_than!(f64::NAN, 1.0f64).is_some());
 
     let failure_message = assert_greater_than!(1, 2)
         .expect("must fail")
@@ -0,0 +1,106 @@
+use crate::assertion_message;
+use k9::assert_iter_equal;
+
+#[test]
+fn test_assert_iter_equal() {
+    super::setup_test_env();
+
+    assert!(assert_iter_equal!(1..4, vec![1, 2, 3]).is_none());
+    assert!(assert_iter_equal!(Vec::<u8>::new(), [0u8; 0]).is_none());
+    assert!(assert_iter_equal!("ab".chars(), ['a', 'b'], "chars of {}", "ab").is_none());
+    // infinite iterator on the left side
+    assert!(assert_iter_equal!((0..).step_by(2), vec![0, 2, 4]).is_some());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_iter_equal!(
+            (0..).map(|i| i * i),
+            vec![0, 1, 4, 9, 16, 24]
+        )),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_iter_equal!((0..).map(|i| i * i), vec![0, 1, 4, 9, 16, 24]);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right` at index 5,
+but items are different:
+
+  ...
+  [2] 4
+  [3] 9
+  [4] 16
+- [5] 25
++ [5] 24
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+}
+
+#[test]
+fn different_lengths() {
+    super::setup_test_env();
+
+    k9_stable::snapshot!(
+        assertion_message(assert_iter_equal!(0..2, vec![0, 1, 2])),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_iter_equal!(0..2, vec![0, 1, 2]);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right` at index 2,
+but `Left` ran out of items:
+
+  [0] 0
+  [1] 1
+- [2] <end of iterator>
++ [2] 2
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    k9_stable::snapshot!(
+        assertion_message(assert_iter_equal!(
+            vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }],
+            vec![Point { x: 0, y: 0 }]
+        )),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_iter_equal!(vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }], vec![Point { x: 0, y: 0 }]);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right` at index 1,
+but `Right` ran out of items:
+
+  [0] Point {
+      x: 0,
+      y: 0,
+  }
+- [1] Point {
+-     x: 1,
+-     y: 1,
+- }
++ [1] <end of iterator>
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+}
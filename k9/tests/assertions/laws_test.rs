@@ -0,0 +1,218 @@
+use crate::assertion_message;
+use k9::{assert_eq_laws, assert_hash_consistent, assert_ord_laws};
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+/// Index key that compares case insensitively, but hashes and orders
+/// the original string
+#[derive(Debug, Clone)]
+struct Key(&'static str);
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(other.0)
+    }
+}
+
+impl Eq for Key {}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(other.0)
+    }
+}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+/// Derived `PartialEq` isn't reflexive for `NaN`
+#[derive(Debug, PartialEq)]
+struct Meters(f64);
+
+/// Equal if the difference is less than 1
+#[derive(Debug)]
+struct Approximately(f64);
+
+impl PartialEq for Approximately {
+    fn eq(&self, other: &Self) -> bool {
+        (self.0 - other.0).abs() < 1.0
+    }
+}
+
+/// Each hand beats the next one, which makes the order cyclic
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Hand {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        use Hand::*;
+        match (self, other) {
+            (Rock, Scissors) | (Paper, Rock) | (Scissors, Paper) => Ordering::Greater,
+            _ if self == other => Ordering::Equal,
+            _ => Ordering::Less,
+        }
+    }
+}
+
+#[test]
+fn eq_laws() {
+    super::setup_test_env();
+
+    assert!(assert_eq_laws!(vec![1, 2, 1, 3]).is_none());
+    assert!(assert_eq_laws!(&[Key("a"), Key("A"), Key("b")]).is_none());
+    assert!(assert_eq_laws!(Vec::<u8>::new(), "no samples").is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_eq_laws!(vec![
+            Approximately(1.0),
+            Approximately(1.5),
+            Approximately(2.2),
+        ])),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_eq_laws!(vec![Approximately(1.0), Approximately(1.5), Approximately(2.2),]);
+
+Assertion Failure!
+
+Expected `Samples` to satisfy `PartialEq` laws,
+but transitivity is violated: `a == b` and `b == c` but `a != c`
+
+a = samples[0]:
+Approximately(
+    1.0,
+)
+
+b = samples[1]:
+Approximately(
+    1.5,
+)
+
+c = samples[2]:
+Approximately(
+    2.2,
+)
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+    k9_stable::snapshot!(
+        assertion_message(assert_eq_laws!(vec![Meters(1.0), Meters(f64::NAN)])),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_eq_laws!(vec![Meters(1.0), Meters(f64::NAN)]);
+
+Assertion Failure!
+
+Expected `Samples` to satisfy `PartialEq` laws,
+but reflexivity is violated: `a != a`
+
+a = samples[1]:
+Meters(
+    NaN,
+)
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+}
+
+#[test]
+fn ord_laws() {
+    super::setup_test_env();
+
+    assert!(assert_ord_laws!(vec![3, 1, 2, 2]).is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_ord_laws!(
+            vec![Key("a"), Key("A")],
+            "Keys must be usable in a BTreeMap"
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_ord_laws!(vec![Key("a"), Key("A")], "Keys must be usable in a BTreeMap");
+
+Keys must be usable in a BTreeMap
+
+Expected `Samples` to satisfy `Ord` laws,
+but consistency with `PartialEq` is violated: `a.cmp(b)` is `Greater` but `a == b`
+
+a = samples[0]:
+Key(
+    "a",
+)
+
+b = samples[1]:
+Key(
+    "A",
+)
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+    k9_stable::snapshot!(
+        assertion_message(assert_ord_laws!(vec![
+            Hand::Rock,
+            Hand::Paper,
+            Hand::Scissors
+        ])),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_ord_laws!(vec![Hand::Rock, Hand::Paper, Hand::Scissors]);
+
+Assertion Failure!
+
+Expected `Samples` to satisfy `Ord` laws,
+but transitivity is violated: `a.cmp(b)` and `b.cmp(c)` are `Less` but `a.cmp(c)` is `Greater`
+
+a = samples[0]:
+Rock
+
+b = samples[1]:
+Paper
+
+c = samples[2]:
+Scissors
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+}
+
+#[test]
+fn hash_consistency() {
+    super::setup_test_env();
+
+    assert!(assert_hash_consistent!(vec![1, 2, 1]).is_none());
+
+    let message = assertion_message(assert_hash_consistent!(vec![Key("b"), Key("a"), Key("A")]));
+    assert!(message.contains("but hash consistency is violated: `a == b` but `hash(a) != hash(b)`"));
+    assert!(message.contains("a = samples[1] (hash 0x"));
+    assert!(message.contains("b = samples[2] (hash 0x"));
+}
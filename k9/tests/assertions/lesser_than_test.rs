@@ -9,7 +9,7 @@ fn test_assert_lesser_than() -> Result<()> {
     assert!(assert_lesser_than!(1, 1).is_some());
     assert!(assert_lesser_than!(1, 0).is_some());
     assert!(assert_lesser_than!("cde", "234").is_some());
-    assert!(assert_lesser_than!(std::f64::NAN, 1.0f64).is_some());
+    assert!(assert_lesser_than!(f64::NAN, 1.0f64).is_some());
 
     let failure_message = assert_lesser_than!(2, 1)
         .expect("must fail")
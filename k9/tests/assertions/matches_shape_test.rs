@@ -0,0 +1,197 @@
+use crate::assertion_message;
+use k9::assert_matches_shape;
+use k9::shape::{any, any_number, any_string};
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Address {
+    street: String,
+    zip: u32,
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct User {
+    id: u64,
+    name: String,
+    email: Option<String>,
+    address: Address,
+    tags: Vec<&'static str>,
+    settings: BTreeMap<&'static str, bool>,
+}
+
+fn user() -> User {
+    User {
+        id: 42,
+        name: "Kelly".to_string(),
+        email: Some("kelly@example.com".to_string()),
+        address: Address {
+            street: "Main st".to_string(),
+            zip: 12345,
+        },
+        tags: vec!["admin", "owner"],
+        settings: vec![("dark_mode", true), ("beta", false)]
+            .into_iter()
+            .collect(),
+    }
+}
+
+#[test]
+fn test_assert_matches_shape() {
+    super::setup_test_env();
+
+    assert!(assert_matches_shape!(user(), { name: "Kelly" }).is_none());
+    assert!(assert_matches_shape!(user(), {
+        id: any_number(),
+        name: any_string(),
+        email: { 0: any_string() },
+        address: { zip: 12345 },
+        tags: ["admin", any()],
+        settings: { "beta" => false },
+    })
+    .is_none());
+    assert!(assert_matches_shape!(vec![(1, "a"), (2, "b")], [{ 0: 1 }, { 1: "b" }]).is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_matches_shape!(user(), {
+            name: "Rob",
+            address: { zip: 54321, city: "Springfield" },
+            tags: ["admin"],
+            settings: { "beta" => true, "legacy" => false },
+        })),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_matches_shape!(user(), {
+    name: "Rob", address: { zip: 54321, city: "Springfield" }, tags:
+    ["admin"], settings: { "beta" => true, "legacy" => false },
+});
+
+Assertion Failure!
+
+
+Expected `Value` to match `Shape`:
+
+  User {
+-     name: "Kelly",
++     name: "Rob",
+      address: Address {
+-         zip: 12345,
++         zip: 54321,
++         city: "Springfield",
+      },
+      tags: [
+          "admin",
+-         "owner",
+      ],
+      settings: {
+-         "beta": false,
++         "beta": true,
++         "legacy": false,
+      },
+  }
+
+Fields missing from `Value`:
+  .address.city
+  .settings["legacy"]
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn matchers() {
+    super::setup_test_env();
+
+    k9_stable::snapshot!(
+        assertion_message(assert_matches_shape!(user(), {
+            id: any_string(),
+            email: any_number(),
+            phone: any(),
+        })),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_matches_shape!(user(), { id: any_string(), email: any_number(), phone: any(), });
+
+Assertion Failure!
+
+
+Expected `Value` to match `Shape`:
+
+  User {
+-     id: 42,
+-     email: Some(
+-         "kelly@example.com",
+-     ),
++     id: <any string>,
++     email: <any number>,
++     phone: <any>,
+  }
+
+Fields missing from `Value`:
+  .phone
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex_matcher() {
+    super::setup_test_env();
+
+    assert!(assert_matches_shape!(user(), {
+        email: { 0: k9::shape::matches_regex("^\\w+@example\\.com$") },
+    })
+    .is_none());
+    assert!(assert_matches_shape!(user(), {
+        name: k9::shape::matches_regex("\\d+"),
+    })
+    .is_some());
+}
+
+#[cfg(feature = "regex")]
+#[test]
+#[should_panic(expected = "matches_regex: invalid regex \"(\"")]
+fn invalid_regex() {
+    k9::shape::matches_regex("(");
+}
+
+#[test]
+fn with_context() {
+    super::setup_test_env();
+
+    k9_stable::snapshot!(
+        assertion_message(assert_matches_shape!(
+            user(),
+            { email: None::<String> },
+            "Expected email to be empty"
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_matches_shape!(user(), { email: None::<String> }, "Expected email to be empty");
+
+Expected email to be empty
+
+
+Expected `Value` to match `Shape`:
+
+  User {
+-     email: Some(
+-         "kelly@example.com",
+-     ),
++     email: None,
+  }
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
@@ -1,24 +1,42 @@
 #![allow(clippy::complexity)]
 
+mod all_test;
+mod any_test;
+mod channel_test;
 mod custom_comparison_formatters_test;
+mod debug_equal_test;
+mod deterministic_test;
+mod display_equal_test;
+mod equal_by_test;
 mod equals_test;
 
 #[cfg(feature = "regex")]
 mod err_matches_regex_test;
 #[cfg(feature = "regex")]
 mod matches_regex_test;
+#[cfg(feature = "serde")]
+mod roundtrip_test;
 
 mod err_test;
 mod greater_than_or_equal_test;
 mod greater_than_test;
+mod iter_equal_test;
+mod laws_test;
 mod lesser_than_or_equal_test;
 mod lesser_than_test;
 mod matches_inline_snapshot_test;
+mod matches_shape_test;
 mod matches_snapshot_test;
 mod ok_test;
+mod ordered_test;
+mod str_equal_test;
 
 fn setup_test_env() {
     k9::config::set_panic(false);
     k9::config::set_terminal_with_override(100);
+    k9::config::set_diff_layout(k9::config::DiffLayout::Unified);
+    k9::config::set_diff_context_lines(3);
+    k9::config::set_diff_line_numbers(false);
+    k9::config::set_max_message_size(usize::MAX);
     colored::control::set_override(true);
 }
@@ -0,0 +1,172 @@
+use crate::assertion_message;
+use k9::assert_ordered;
+use std::cell::Cell;
+
+#[derive(Debug, PartialEq, PartialOrd)]
+struct Version(u32, u32);
+
+/// Versions can be compared to a major version number
+impl PartialEq<u32> for Version {
+    fn eq(&self, major: &u32) -> bool {
+        self.0 == *major && self.1 == 0
+    }
+}
+
+impl PartialOrd<u32> for Version {
+    fn partial_cmp(&self, major: &u32) -> Option<std::cmp::Ordering> {
+        Some(self.0.cmp(major).then(self.1.cmp(&0)))
+    }
+}
+
+#[test]
+fn test_assert_ordered() {
+    super::setup_test_env();
+
+    let (low, value, high) = (1, 5, 10);
+    assert!(assert_ordered!(low < value <= high).is_none());
+    assert!(assert_ordered!(high >= value > low >= 1).is_none());
+    assert!(assert_ordered!(value <= 5 <= value).is_none());
+    assert!(assert_ordered!("a" < "b").is_none());
+    assert!(assert_ordered!(Version(1, 2) < Version(1, 10)).is_none());
+    assert!(assert_ordered!(f64::NAN < 1.0).is_some());
+
+    // operands of different types
+    assert!(assert_ordered!(Version(1, 0) < Version(1, 2) < 2).is_none());
+    assert!(assert_ordered!(Version(2, 1) <= 2).is_some());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_ordered!(low < value <= 3)),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_ordered!(low < value <= 3);
+
+Assertion Failure!
+
+Expected `low < value <= 3` to hold,
+but `value <= 3` doesn't hold, `value` is greater than `3`
+
+low:   1
+value: 5
+3:     3
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+    k9_stable::snapshot!(
+        assertion_message(assert_ordered!(
+            vec![1, 2] < vec![1, 2] <= vec![1] > vec![0, 9]
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_ordered!(vec![1, 2] < vec![1, 2] <= vec![1] > vec![0, 9]);
+
+Assertion Failure!
+
+Expected `vec![1, 2] < vec![1, 2] <= vec![1] > vec![0, 9]` to hold,
+but `vec![1, 2] < vec![1, 2]` doesn't hold, `vec![1, 2]` is equal to `vec![1, 2]`
+and `vec![1, 2] <= vec![1]` doesn't hold, `vec![1, 2]` is greater than `vec![1]`
+
+vec![1, 2]: [
+                1,
+                2,
+            ]
+vec![1, 2]: [
+                1,
+                2,
+            ]
+vec![1]:    [
+                1,
+            ]
+vec![0, 9]: [
+                0,
+                9,
+            ]
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+    k9_stable::snapshot!(
+        assertion_message(assert_ordered!(
+            0.0 <= f64::NAN < 1.0,
+            "probability must be within {}..{}",
+            0,
+            1
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_ordered!(0.0 <= f64::NAN < 1.0, "probability must be within {}..{}", 0, 1);
+
+probability must be within 0..1
+
+Expected `0.0 <= f64::NAN < 1.0` to hold,
+but `0.0 <= f64::NAN` doesn't hold, `0.0` and `f64::NAN` can't be compared
+and `f64::NAN < 1.0` doesn't hold, `f64::NAN` and `1.0` can't be compared
+
+0.0:      0.0
+f64::NAN: NaN
+1.0:      1.0
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn evaluates_operands_once() {
+    super::setup_test_env();
+
+    let calls = Cell::new(0);
+    let next = || {
+        calls.set(calls.get() + 1);
+        calls.get()
+    };
+
+    assert!(assert_ordered!(0 < next() < 2 <= next() < 3).is_none());
+    k9_stable::assert_equal!(calls.get(), 2);
+}
+
+#[test]
+fn generic_operands() {
+    super::setup_test_env();
+
+    let v = [1u32, 2, 3];
+    assert!(assert_ordered!(v.len() < "5".parse::<usize>().unwrap()).is_none());
+    assert!(
+        assert_ordered!(v.iter().sum::<u32>() >= 6 > v.iter().copied().min().unwrap()).is_none()
+    );
+    assert!(assert_ordered!(Vec::<u8>::new().len() < v.len()).is_none());
+    assert!(
+        assert_ordered!(Vec::<Vec<u8>>::new().len() <= Vec::<Vec<Vec<u8>>>::new().len()).is_none()
+    );
+    assert!(assert_ordered!(<u32 as Default>::default() < v[0]).is_none());
+    assert!(assert_ordered!(Vec::<Box<dyn Fn(u8) -> u8>>::new().len() < 1).is_none());
+    assert!(assert_ordered!(v.iter().map(|x| x * 2).collect::<Vec<u32>>() > vec![1]).is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_ordered!(
+            v.iter().sum::<u32>() < "5".parse::<u32>().unwrap()
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_ordered!(v.iter().sum::<u32>() < "5".parse::<u32>().unwrap());
+
+Assertion Failure!
+
+Expected `v.iter().sum::<u32>() < "5".parse::<u32>().unwrap()` to hold,
+but `v.iter().sum::<u32>() < "5".parse::<u32>().unwrap()` doesn't hold, `v.iter().sum::<u32>()` is greater than `"5".parse::<u32>().unwrap()`
+
+v.iter().sum::<u32>():       6
+"5".parse::<u32>().unwrap(): 5
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
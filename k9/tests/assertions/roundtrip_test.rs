@@ -0,0 +1,48 @@
+use crate::assertion_message;
+use k9::assert_roundtrip;
+
+// json and bincode formats are tested in their own binary (tests/roundtrip_test.rs)
+
+#[test]
+fn custom_format() {
+    super::setup_test_env();
+
+    assert!(assert_roundtrip!(
+        42u32,
+        format = custom(
+            |v: &u32| Ok::<_, String>(v.to_string()),
+            |s: &String| s.parse::<u32>()
+        )
+    )
+    .is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_roundtrip!(
+            -5i32,
+            format = custom(
+                |v: &i32| Ok::<_, String>(v.abs().to_string()),
+                |s: &String| s.parse::<i32>()
+            )
+        )),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_roundtrip!(-5i32, format = custom
+(|v: &i32| Ok::<_, String>(v.abs().to_string()),
+|s: &String| s.parse::<i32>()));
+
+Assertion Failure!
+
+Expected `Value` to equal `Deserialized` after a roundtrip through custom format:
+
+- -5
++ 5
+
+Serialized custom format:
+5
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+}
@@ -0,0 +1,91 @@
+use crate::assertion_message;
+use k9::assert_str_equal;
+
+#[test]
+fn test_assert_str_equal() {
+    super::setup_test_env();
+
+    assert!(assert_str_equal!("abc", String::from("abc")).is_none());
+    assert!(assert_str_equal!("abc", "abc", "with description {}", 1).is_none());
+    assert!(assert_str_equal!("abc", "abc ").is_some());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_str_equal!(
+            "line 1\nline 2\nline 3",
+            "line 1\nline two\nline 3"
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_str_equal!("line 1\
+line 2\
+line 3", "line 1\
+line two\
+line 3");
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+  line 1
+- line 2
++ line two
+  line 3
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn normalizations() {
+    super::setup_test_env();
+
+    assert!(assert_str_equal!("a\r\nb\r\n", "a\nb\n", normalize = [crlf]).is_none());
+    assert!(assert_str_equal!("a  \t b", "a b", normalize = [collapse_whitespace]).is_none());
+    assert!(assert_str_equal!("\n  a b \n", "a b", normalize = [trim]).is_none());
+    assert!(assert_str_equal!("a\nb\n\n", "a\nb", normalize = [trailing_newline]).is_none());
+    assert!(
+        assert_str_equal!("a\r\nb\r\n", "a\nb", normalize = [trailing_newline, crlf]).is_none()
+    );
+    assert!(assert_str_equal!("Hello World", "hello world", normalize = [case]).is_none());
+    assert!(assert_str_equal!("Hello\nWorld", "hello world", normalize = [case]).is_some());
+
+    // Difference is displayed for the original strings
+    k9_stable::snapshot!(
+        assertion_message(assert_str_equal!(
+            "SELECT *\r\n  FROM   users\r\n WHERE id = 1\r\n",
+            "select *\nfrom users\nwhere id = 2",
+            normalize = [crlf, collapse_whitespace, trim, case],
+            "Queries must match"
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_str_equal!("SELECT *\\r\
+  FROM   users\\r\
+ WHERE id = 1\\r\
+", "select *\
+from users\
+where id = 2", normalize = [crlf, collapse_whitespace, trim, case], "Queries must match");
+
+Queries must match
+
+
+Expected `Left` to equal `Right` after normalizing with [crlf, collapse_whitespace, trim, case]:
+
+- SELECT *
+-   FROM   users
+-  WHERE id = 1
++ select *
++ from users
++ where id = 2
++ ⏎ missing newline at end
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "log")]
+
+use k9::{capture_log, snapshot, Snap};
+
+#[test]
+fn captures_records() {
+    let logs = Snap::new();
+    let result = capture_log(&logs, || {
+        log::error!("failed to connect");
+        log::warn!(target: "app::db", "slow query");
+        log::info!(user = "kelly", id = 42; "logged in");
+        log::debug!("debug");
+        log::trace!("trace");
+        5
+    });
+
+    assert_eq!(result, 5);
+    snapshot!(
+        logs.to_string(),
+        r#"
+ERROR capture_log_test: failed to connect
+WARN app::db: slow query
+INFO capture_log_test: logged in user="kelly" id=42
+DEBUG capture_log_test: debug
+TRACE capture_log_test: trace
+
+"#
+    );
+}
+
+#[test]
+fn only_captures_current_thread() {
+    let logs = Snap::new();
+    capture_log(&logs, || {
+        std::thread::spawn(|| log::info!("other thread"))
+            .join()
+            .unwrap();
+        log::info!("this thread");
+    });
+    log::info!("outside of capture");
+
+    snapshot!(
+        logs.to_string(),
+        "
+INFO capture_log_test: this thread
+
+"
+    );
+}
+
+#[test]
+fn nested_captures() {
+    let outer = Snap::new();
+    let inner = Snap::new();
+    capture_log(&outer, || {
+        log::info!("before");
+        capture_log(&inner, || log::info!("inner"));
+        log::info!("after");
+    });
+
+    snapshot!(
+        outer.to_string(),
+        "
+INFO capture_log_test: before
+INFO capture_log_test: after
+
+"
+    );
+    snapshot!(
+        inner.to_string(),
+        "
+INFO capture_log_test: inner
+
+"
+    );
+}
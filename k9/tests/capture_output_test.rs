@@ -0,0 +1,100 @@
+#![cfg(unix)]
+
+use k9::{assert_equal, capture_output, snapshot_output, CapturedOutput};
+use std::io::Write;
+use std::os::raw::{c_char, c_int};
+
+extern "C" {
+    fn puts(s: *const c_char) -> c_int;
+}
+
+fn print_from_c(s: &str) {
+    let s = std::ffi::CString::new(s).unwrap();
+    unsafe {
+        puts(s.as_ptr());
+    }
+}
+
+#[test]
+fn captures_stdout_and_stderr() {
+    let output = capture_output(|| {
+        println!("println");
+        print!("print without newline ");
+        writeln!(std::io::stdout(), "direct write").unwrap();
+        eprintln!("eprintln");
+    });
+
+    assert_equal!(
+        output,
+        CapturedOutput {
+            stdout: "println\nprint without newline direct write\n".to_string(),
+            stderr: "eprintln\n".to_string(),
+        }
+    );
+}
+
+#[test]
+fn captures_c_output() {
+    let output = capture_output(|| {
+        print_from_c("from C");
+    });
+
+    assert_equal!(output.stdout, "from C\n");
+}
+
+#[test]
+fn borrows_from_the_caller() {
+    let mut lines = vec![];
+    let output = capture_output(|| {
+        for i in 0..3 {
+            println!("line {}", i);
+            lines.push(i);
+        }
+    });
+
+    assert_equal!(lines, vec![0, 1, 2]);
+    assert_equal!(output.to_string(), "stdout:\nline 0\nline 1\nline 2\n");
+}
+
+#[test]
+fn parallel_captures_dont_interleave() {
+    let threads = (0..4)
+        .map(|i| {
+            std::thread::spawn(move || {
+                capture_output(|| {
+                    for _ in 0..100 {
+                        println!("thread {}", i);
+                    }
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (i, thread) in threads.into_iter().enumerate() {
+        let output = thread.join().unwrap();
+        assert_equal!(output.stdout, format!("thread {}\n", i).repeat(100));
+    }
+}
+
+#[test]
+#[should_panic(expected = "boom")]
+fn propagates_panics() {
+    capture_output(|| panic!("boom"));
+}
+
+#[test]
+fn snapshot() {
+    snapshot_output!(
+        || {
+            println!("Usage: cli [OPTIONS]");
+            eprintln!("error: missing argument");
+        },
+        "
+stdout:
+Usage: cli [OPTIONS]
+stderr:
+error: missing argument
+
+"
+    );
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "tracing")]
+
+use k9::{capture_tracing, snapshot, Snap};
+
+#[test]
+fn captures_events() {
+    let logs = Snap::new();
+    let result = capture_tracing(&logs, || {
+        tracing::error!("failed to connect");
+        tracing::warn!(target: "app::db", elapsed_ms = 1200, "slow query");
+        tracing::info!(user = "kelly", ok = true);
+        5
+    });
+
+    assert_eq!(result, 5);
+    snapshot!(
+        logs.to_string(),
+        r#"
+ERROR capture_tracing_test: failed to connect
+WARN app::db: slow query elapsed_ms=1200
+INFO capture_tracing_test: user="kelly" ok=true
+
+"#
+    );
+}
+
+#[test]
+fn span_nesting() {
+    let logs = Snap::new();
+    capture_tracing(&logs, || {
+        let request =
+            tracing::info_span!("request", method = "GET", status = tracing::field::Empty);
+        request.in_scope(|| {
+            tracing::info!("started");
+            tracing::debug_span!("db").in_scope(|| {
+                tracing::debug!(rows = 3, "query");
+            });
+            request.record("status", 200);
+            tracing::info!("finished");
+        });
+        tracing::info!("idle");
+        request.in_scope(|| tracing::info!("reentered"));
+    });
+
+    snapshot!(
+        logs.to_string(),
+        r#"
+request{method="GET"}
+    INFO capture_tracing_test: started
+    db
+        DEBUG capture_tracing_test: query rows=3
+    INFO capture_tracing_test: finished
+INFO capture_tracing_test: idle
+request{method="GET" status=200}
+    INFO capture_tracing_test: reentered
+
+"#
+    );
+}
+
+#[test]
+fn cloned_spans() {
+    let logs = Snap::new();
+    capture_tracing(&logs, || {
+        let span = tracing::info_span!("job", id = 7);
+        let handle = span.clone();
+        drop(handle);
+        span.in_scope(|| tracing::info!("still has a header"));
+    });
+
+    snapshot!(
+        logs.to_string(),
+        "
+job{id=7}
+    INFO capture_tracing_test: still has a header
+
+"
+    );
+}
+
+#[test]
+fn only_captures_current_thread() {
+    let logs = Snap::new();
+    capture_tracing(&logs, || {
+        std::thread::spawn(|| tracing::info!("other thread"))
+            .join()
+            .unwrap();
+        tracing::info!("this thread");
+    });
+    tracing::info!("outside of capture");
+
+    snapshot!(
+        logs.to_string(),
+        "
+INFO capture_tracing_test: this thread
+
+"
+    );
+}
@@ -0,0 +1,125 @@
+use k9::config::{set_diff_layout, set_diff_line_numbers, set_terminal_with_override, DiffLayout};
+use k9::{assert_equal, MultilineString};
+
+// Line numbers and diff layout are global, so these tests get their own binary
+
+fn assertion_message(m: Option<k9::assertions::Assertion>) -> String {
+    String::from_utf8(
+        strip_ansi_escapes::strip(m.expect("Assertion must fail").get_failure_message()).unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn line_numbers() {
+    k9::config::set_panic(false);
+    colored::control::set_override(true);
+    set_diff_line_numbers(true);
+    k9::config::set_max_message_size(usize::MAX);
+    set_terminal_with_override(80);
+
+    let left = MultilineString::new("fn main() {\n    let x = 1;\n    run(x);\n}\n");
+    let right =
+        MultilineString::new("fn main() {\n    let x = 2;\n    let y = 3;\n    run(x);\n}\n");
+
+    set_diff_layout(DiffLayout::Unified);
+    let message = assertion_message(assert_equal!(left, right));
+    k9_stable::snapshot!(
+        message,
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal!(left, right);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+1 1 │   fn main() {
+2   │ -     let x = 1;
+  2 │ +     let x = 2;
+  3 │ +     let y = 3;
+3 4 │       run(x);
+4 5 │   }
+    │   
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+
+    set_diff_layout(DiffLayout::SideBySide);
+    let message = assertion_message(assert_equal!(left, right));
+    k9_stable::snapshot!(
+        message,
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal!(left, right);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+1   fn main() {                        │ 1   fn main() {
+2 -     let x = 1;                     │ 2 +     let x = 2;
+                                       │ 3 +     let y = 3;
+3       run(x);                        │ 4       run(x);
+4   }                                  │ 5   }
+                                       │  
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+
+    // inline snapshots are numbered with the source lines of the literal
+    set_diff_layout(DiffLayout::Unified);
+    let literal_line = line!() as usize + 3;
+    let message = assertion_message(k9::snapshot!(
+        MultilineString::new("a\nb\nX"),
+        "
+a
+b
+c
+"
+    ));
+    assert!(message.contains(&format!("{}    │ - c\n    4 │ + X\n", literal_line + 3)));
+
+    // long lines with the change far from the start still get line numbers
+    let long_line = |c: char| format!("a\n{}{}{}\nb\n", "x".repeat(100), c, "y".repeat(150));
+    let message = assertion_message(assert_equal!(
+        MultilineString::new(long_line('1')),
+        MultilineString::new(long_line('2'))
+    ));
+    k9_stable::snapshot!(
+        message,
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal!(MultilineString::new(long_line('1')), MultilineString::new(long_line('2')));
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+1 1 │   a
+    │   ⋯ 60 unchanged characters ⋯
+2   │ - xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx1 yyyyyyyyyyyyyyyyyyyyyyyyyyyyyy
+  2 │ + xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx 2yyyyyyyyyyyyyyyyyyyyyyyyyyyyyy
+    │                                           ^^
+    │ - yyyyyyyyyy
+    │ + yyyyyyyyyy
+    │   
+    │   ⋯ 110 unchanged characters ⋯
+3 3 │   b
+    │   
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+}
@@ -0,0 +1,137 @@
+use k9::assert_equal;
+use k9::config::{
+    set_artifacts_dir, set_diff_layout, set_max_message_size, set_terminal_with_override,
+    DiffLayout,
+};
+
+// Message size and artifacts directory are global, so these tests get their own binary
+
+fn assertion_message(m: Option<k9::assertions::Assertion>) -> String {
+    String::from_utf8(
+        strip_ansi_escapes::strip(m.expect("Assertion must fail").get_failure_message()).unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn truncates_huge_messages() {
+    k9::config::set_panic(false);
+    colored::control::set_override(true);
+    set_terminal_with_override(100);
+    set_diff_layout(DiffLayout::Unified);
+    k9::config::set_diff_context_lines(3);
+    k9::config::set_diff_line_numbers(false);
+    set_max_message_size(1000);
+    let artifacts_dir = std::env::temp_dir().join("k9-message-size-test");
+    set_artifacts_dir(&artifacts_dir);
+
+    let left = (0..200).collect::<Vec<u32>>();
+    let right = (0..200).map(|i| i * 2).collect::<Vec<u32>>();
+
+    let message = assertion_message(assert_equal!(&left, &right));
+    let message = message.replace(&artifacts_dir.display().to_string(), "<artifacts>");
+    k9_stable::snapshot!(message, "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal!(&left, &right);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+Differences:
+  [1]: removed 1
+  [3]: removed 3
+  [5]: removed 5
+  [7]: removed 7
+  [9]: removed 9
+  [11]: removed 11
+  [13]: removed 13
+  [15]: removed 15
+  [17]: removed 17
+  [19]: removed 19
+  ... and 189 more
+
+Values are too large to print in full, `Left` has 202 lines (1693 characters) and `Right` has 202 lines (1748 characters)
+
+  [
+      0,
+-     1,
+      2,
+-     3,
+      4,
+-     5,
+      6,
+-     7,
+      8,
+-     9,
+      10,
+-     11,
+      12,
+-     13,
+      14,
+-     15,
+      16,
+-     17,
+      18,
+-     19,
+      20,
+-     21,
+      22,
+-     23,
+      24,
+-     25,
+      26,
+-     27,
+      28,
+-     29,
+      30,
+-     31,
+      32,
+-     33,
+      34,
+-     35,
+      36,
+-     37,
+      38,
+-     39,
+  ⋯ 261 more lines of the diff not shown ⋯
+
+Full values and diff were written to:
+  <artifacts>/truncates_huge_messages/1/left.txt
+  <artifacts>/truncates_huge_messages/1/right.txt
+  <artifacts>/truncates_huge_messages/1/diff.txt
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+");
+
+    let test_dir = artifacts_dir.join("truncates_huge_messages");
+    let read = |name| std::fs::read_to_string(test_dir.join("1").join(name)).unwrap();
+    k9_stable::assert_equal!(read("left.txt"), format!("{:#?}", left));
+    k9_stable::assert_equal!(read("right.txt"), format!("{:#?}", right));
+    let diff = read("diff.txt");
+    assert!(!diff.contains('\u{1b}'));
+    assert!(diff.contains("-     199,\n"));
+
+    // every failure gets its own files
+    let other = (0..200).map(|i| i * 3).collect::<Vec<u32>>();
+    let message = assertion_message(assert_equal!(&left, &other));
+    assert!(message.contains(&test_dir.join("2").join("left.txt").display().to_string()));
+    k9_stable::assert_equal!(
+        std::fs::read_to_string(test_dir.join("2").join("right.txt")).unwrap(),
+        format!("{:#?}", other)
+    );
+    k9_stable::assert_equal!(read("right.txt"), format!("{:#?}", right));
+
+    // small enough values are printed in full
+    let message = assertion_message(assert_equal!(&left[..3], &right[..3]));
+    assert!(!message.contains("too large"));
+
+    // other assertions comparing values are truncated the same way
+    let message = assertion_message(k9::assert_equal_by!(&left, &right, |a, b| a == b));
+    assert!(message.contains("to equal `Right` by `Comparator`"));
+    assert!(message.contains("Values are too large to print in full"));
+    assert!(message.contains(&test_dir.join("3").join("diff.txt").display().to_string()));
+}
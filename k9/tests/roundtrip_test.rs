@@ -0,0 +1,187 @@
+#![cfg(all(feature = "serde_json", feature = "bincode"))]
+
+use k9::assert_roundtrip;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+// Linking `serde_json` breaks type inference in the other assertion tests,
+// so json and bincode roundtrips get their own binary
+
+fn setup_test_env() {
+    k9::config::set_panic(false);
+    k9::config::set_terminal_with_override(100);
+    k9::config::set_diff_layout(k9::config::DiffLayout::Unified);
+    k9::config::set_diff_context_lines(3);
+    k9::config::set_diff_line_numbers(false);
+    k9::config::set_max_message_size(usize::MAX);
+    colored::control::set_override(true);
+}
+
+fn assertion_message(m: Option<k9::assertions::Assertion>) -> String {
+    String::from_utf8(
+        strip_ansi_escapes::strip(m.expect("Assertion must fail").get_failure_message()).unwrap(),
+    )
+    .unwrap()
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Message {
+    id: u32,
+    tags: Vec<String>,
+    headers: BTreeMap<String, String>,
+}
+
+fn message() -> Message {
+    Message {
+        id: 7,
+        tags: vec!["urgent".to_string()],
+        headers: vec![("content-type".to_string(), "text".to_string())]
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Loses the sign during serialization
+#[derive(Debug, PartialEq, Serialize)]
+struct Lossy(#[serde(serialize_with = "serialize_abs")] i32);
+
+fn serialize_abs<S: serde::Serializer>(v: &i32, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_i32(v.abs())
+}
+
+impl<'de> Deserialize<'de> for Lossy {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        i32::deserialize(d).map(Lossy)
+    }
+}
+
+#[test]
+fn test_assert_roundtrip() {
+    setup_test_env();
+
+    assert!(assert_roundtrip!(message()).is_none());
+    assert!(assert_roundtrip!(message(), format = json).is_none());
+    assert!(assert_roundtrip!(message(), format = bincode, "bincode {}", 1).is_none());
+    assert!(assert_roundtrip!(
+        message(),
+        format = custom(serde_json::to_string_pretty, |s: &String| {
+            serde_json::from_str(s)
+        })
+    )
+    .is_none());
+    assert!(assert_roundtrip!(Lossy(5)).is_none());
+
+    k9_stable::snapshot!(
+        assertion_message(assert_roundtrip!(
+            Lossy(-5),
+            "sign must survive serialization"
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_roundtrip!(Lossy(-5), format = json, "sign must survive serialization");
+
+sign must survive serialization
+
+Expected `Value` to equal `Deserialized` after a roundtrip through json:
+
+  Lossy(
+-     -5,
++     5,
+  )
+
+Serialized json:
+5
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn errors() {
+    setup_test_env();
+
+    let mut map = BTreeMap::new();
+    map.insert((1, 2), "tuple keys are not supported by json".to_string());
+    k9_stable::snapshot!(
+        assertion_message(assert_roundtrip!(map)),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_roundtrip!(map, format = json);
+
+Assertion Failure!
+
+Failed to serialize `Value` as json:
+key must be a string
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+
+    k9_stable::snapshot!(
+        assertion_message(assert_roundtrip!(
+            message(),
+            format = custom(
+                |m: &Message| serde_json::to_string(m),
+                |_: &String| serde_json::from_str::<Message>("{}")
+            )
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_roundtrip!(message(), format = custom
+(|m: &Message| serde_json::to_string(m),
+|_: &String| serde_json::from_str::<Message>("{}")));
+
+Assertion Failure!
+
+Failed to deserialize `Value` back from custom format:
+missing field `id` at line 1 column 2
+
+Serialized custom format:
+{"id":7,"tags":["urgent"],"headers":{"content-type":"text"}}
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+}
+
+#[test]
+fn snapshots() {
+    setup_test_env();
+
+    // snapshot macros can't be nested in other macros, since their source
+    // location is looked up when updating snapshots
+    let json = assert_roundtrip!(
+        message(),
+        snapshot = r#"
+{
+  "id": 7,
+  "tags": [
+    "urgent"
+  ],
+  "headers": {
+    "content-type": "text"
+  }
+}
+"#
+    );
+    assert!(json.is_none());
+    let bincode = assert_roundtrip!(
+        message(),
+        format = bincode,
+        snapshot = "
+07 00 00 00 01 00 00 00 00 00 00 00 06 00 00 00
+00 00 00 00 75 72 67 65 6e 74 01 00 00 00 00 00
+00 00 0c 00 00 00 00 00 00 00 63 6f 6e 74 65 6e
+74 2d 74 79 70 65 04 00 00 00 00 00 00 00 74 65
+78 74
+"
+    );
+    assert!(bincode.is_none());
+}
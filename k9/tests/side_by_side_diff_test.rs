@@ -0,0 +1,139 @@
+use k9::config::{set_diff_layout, set_diff_line_numbers, set_terminal_with_override, DiffLayout};
+use k9::{assert_equal, MultilineString};
+
+// Diff layout and terminal width are global, so these tests get their own binary
+
+fn assertion_message(m: Option<k9::assertions::Assertion>) -> String {
+    String::from_utf8(
+        strip_ansi_escapes::strip(m.expect("Assertion must fail").get_failure_message()).unwrap(),
+    )
+    .unwrap()
+}
+
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: &'static str,
+    path: &'static str,
+    headers: Vec<(&'static str, &'static str)>,
+    body: Option<&'static str>,
+}
+
+#[test]
+fn side_by_side() {
+    k9::config::set_panic(false);
+    colored::control::set_override(true);
+    set_diff_layout(DiffLayout::SideBySide);
+    set_diff_line_numbers(false);
+    k9::config::set_max_message_size(usize::MAX);
+    set_terminal_with_override(80);
+
+    let left = Request {
+        method: "GET",
+        path: "/users/42",
+        headers: vec![("Accept", "application/json")],
+        body: None,
+    };
+    let right = Request {
+        method: "POST",
+        path: "/users/42",
+        headers: vec![
+            ("Accept", "application/json"),
+            ("Content-Type", "application/json; charset=utf-8"),
+        ],
+        body: Some("{\"name\": \"Kelly\"}"),
+    };
+
+    k9_stable::snapshot!(
+        assertion_message(assert_equal!(left, right)),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal!(left, right);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+Differences:
+  .method: "GET" → "POST"
+  .headers[1]: added ("Content-Type", "application/json; charset=utf-8")
+  .body: None → Some("{\\"name\\": \\"Kelly\\"}")
+
+  Request {                            │   Request {
+-     method: "GET",                   │ +     method: "POST",
+      path: "/users/42",               │       path: "/users/42",
+      headers: [                       │       headers: [
+          (                            │           (
+              "Accept",                │               "Accept",
+              "application/json",      │               "application/json",
+          ),                           │           ),
+                                       │ +         (
+                                       │ +             "Content-Type",
+                                       │ +             "application/json; chars
+                                       │   et=utf-8",
+                                       │ +         ),
+      ],                               │       ],
+-     body: None,                      │ +     body: Some(
+                                       │ +         "{\\"name\\": \\"Kelly\\"}",
+                                       │ +     ),
+  }                                    │   }
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+
+    // lines that don't fit into the column are wrapped
+    k9_stable::snapshot!(
+        assertion_message(assert_equal!(
+            MultilineString::new(
+                "short\nThe quick brown fox jumps over the lazy dog and keeps running"
+            ),
+            MultilineString::new(
+                "short\nThe quick brown fox leaps over the lazy dog and keeps running"
+            )
+        )),
+        r#"
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal!(MultilineString::new("short\
+The quick brown fox jumps over the lazy dog and keeps running"), MultilineString::new("short\
+The quick brown fox leaps over the lazy dog and keeps running"));
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+  short                                │   short
+- The quick brown fox jumps over the l │ + The quick brown fox leaps over the l
+  azy dog and keeps running            │   azy dog and keeps running
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"#
+    );
+
+    // not enough space for two columns
+    set_terminal_with_override(60);
+    k9_stable::snapshot!(
+        assertion_message(assert_equal!(1, 2)),
+        "
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+assert_equal!(1, 2);
+
+Assertion Failure!
+
+
+Expected `Left` to equal `Right`:
+
+- 1
++ 2
+
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+"
+    );
+}
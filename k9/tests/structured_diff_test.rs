@@ -0,0 +1,157 @@
+use k9::config::DiffAlgorithm;
+use k9::string_diff::{
+    render_html, render_plain, render_unified_patch, Diff, DiffOptions, DiffStats, LineOp, Side,
+};
+
+// `colored_diff` depends on global config, so these tests get their own binary
+
+fn options() -> DiffOptions {
+    DiffOptions {
+        context: 1,
+        ..DiffOptions::default()
+    }
+}
+
+const LEFT: &str = "fn main() {
+    let x = 1;
+    a();
+    b();
+    c();
+    d();
+    e();
+    f();
+    run(x);
+}
+";
+const RIGHT: &str = "fn main() {
+    let x = 10;
+    a();
+    b();
+    c();
+    d();
+    e();
+    f();
+    run(x);
+    stop();
+}";
+
+#[test]
+fn structured_diff() {
+    let diff = Diff::with_options(LEFT, RIGHT, options());
+
+    k9_stable::assert_equal!(
+        diff.stats,
+        DiffStats {
+            added: 2,
+            removed: 1,
+            unchanged: 9
+        }
+    );
+    k9_stable::assert_equal!(diff.missing_newline, Some(Side::Right));
+    k9_stable::assert_equal!(diff.hunks.len(), 2);
+    k9_stable::assert_equal!(diff.skipped_at_end, 0);
+
+    let first = &diff.hunks[0];
+    k9_stable::assert_equal!(
+        (first.skipped, first.left_start, first.right_start),
+        (0, 1, 1)
+    );
+    let removed = &first.lines[1];
+    k9_stable::assert_equal!(removed.op, LineOp::Removed);
+    k9_stable::assert_equal!((removed.left_number, removed.right_number), (Some(2), None));
+    k9_stable::assert_equal!(&removed.text[removed.changed_ranges[0].clone()], "1");
+    let added = &first.lines[2];
+    k9_stable::assert_equal!(&added.text[added.changed_ranges[0].clone()], "10");
+
+    let second = &diff.hunks[1];
+    k9_stable::assert_equal!(
+        (second.skipped, second.left_start, second.right_start),
+        (5, 9, 9)
+    );
+
+    assert!(Diff::new(LEFT, LEFT).is_empty());
+    assert!(Diff::new("a  b\n", "a b").is_whitespace_only());
+    assert!(!diff.is_whitespace_only());
+
+    // 3 lines of context by default, so the hunks are merged
+    k9_stable::assert_equal!(Diff::new(LEFT, RIGHT).hunks.len(), 1);
+
+    let (left, right) = ("}\n}\nfn run() {\n", "fn run() {\n}\n}\n");
+    let first_op = |algorithm| {
+        let options = DiffOptions {
+            algorithm,
+            ..DiffOptions::default()
+        };
+        Diff::with_options(left, right, options).hunks[0].lines[0].op
+    };
+    k9_stable::assert_equal!(first_op(DiffAlgorithm::Myers), LineOp::Added);
+    k9_stable::assert_equal!(first_op(DiffAlgorithm::Patience), LineOp::Removed);
+}
+
+#[test]
+fn renderers() {
+    let diff = Diff::with_options(LEFT, RIGHT, options());
+
+    k9_stable::snapshot!(
+        render_plain(&diff),
+        "
+  fn main() {
+-     let x = 1;
++     let x = 10;
+      a();
+  ⋯ 5 unchanged lines ⋯
+      run(x);
++     stop();
+  }
++ ⏎ missing newline at end
+
+"
+    );
+    k9_stable::snapshot!(
+        render_unified_patch(&diff, "a/main.rs", "b/main.rs"),
+        r#"
+--- a/main.rs
++++ b/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 10;
+     a();
+@@ -9,2 +9,3 @@
+     run(x);
++    stop();
+-}
++}
+\\ No newline at end of file
+
+"#
+    );
+    k9_stable::snapshot!(
+        render_html(&Diff::new("<a> & b\nc\n", "<a> & d\nc\n")),
+        r#"
+<pre class="k9-diff">
+<div class="k9-removed" data-left="1">- &lt;a&gt; &amp; <mark>b</mark></div>
+<div class="k9-added" data-right="1">+ &lt;a&gt; &amp; <mark>d</mark></div>
+<div class="k9-unchanged" data-left="2" data-right="2">  c</div>
+</pre>
+
+"#
+    );
+}
+
+#[test]
+fn colored_diff_follows_config() {
+    k9::config::set_terminal_with_override(100);
+    k9::config::set_diff_layout(k9::config::DiffLayout::SideBySide);
+    k9::config::set_diff_context_lines(1);
+    k9::config::set_diff_line_numbers(true);
+    k9::config::set_diff_algorithm(DiffAlgorithm::Myers);
+    colored::control::set_override(true);
+
+    let diff = Diff::with_options(LEFT, RIGHT, DiffOptions::from_config());
+    k9_stable::assert_equal!(diff, Diff::with_options(LEFT, RIGHT, options()));
+    k9_stable::assert_equal!(
+        k9::string_diff::colored_diff(LEFT, RIGHT),
+        Some(k9::string_diff::render_colored(&diff))
+    );
+}
@@ -0,0 +1,71 @@
+use crate::support::TestProject;
+use anyhow::Result;
+
+const TYPES: &str = r#"
+pub struct NotClone;
+pub struct NotSend(*const u8);
+"#;
+
+#[test]
+fn compile_time_assertions() -> Result<()> {
+    let p = TestProject::new("compile_time_assertions");
+
+    p.write_file("Cargo.toml", crate::support::TEST_CARGO_TOML)?;
+
+    p.write_file(
+        "lib.rs",
+        &format!(
+            r#"
+{}
+k9::assert_impl!(u8: Send + Sync + Clone);
+k9::assert_impl!(Vec<NotClone>: Send + Sync);
+k9::assert_not_impl!(NotSend: Send);
+k9::assert_not_impl!(NotClone: Clone);
+k9::assert_size!(u64, 8);
+k9::assert_size!(NotSend, std::mem::size_of::<usize>());
+
+pub fn in_function_body() {{
+    k9::assert_impl!(String: Clone);
+    k9::assert_not_impl!(NotSend: Sync);
+    k9::assert_size!((u32, u32), 8);
+}}
+"#,
+            TYPES
+        ),
+    )?;
+
+    let test_run = p.run_tests().build().unwrap().run()?;
+    test_run.assert_success()?;
+
+    p.write_file(
+        "lib.rs",
+        &format!(
+            r#"
+{}
+k9::assert_impl!(NotClone: Clone);
+k9::assert_impl!(NotSend: Send);
+k9::assert_not_impl!(u8: Send);
+k9::assert_size!((u64, u8), 9);
+"#,
+            TYPES
+        ),
+    )?;
+
+    let test_run = p.run_tests().build().unwrap().run()?;
+
+    k9_released::snapshot!(
+        test_run.compilation_errors()?,
+        r#"
+[
+    "error[E0277]: the trait bound `NotClone: Clone` is not satisfied",
+    "error[E0277]: `*const u8` cannot be sent between threads safely",
+    "error[E0080]: evaluation panicked: `u8` must not implement `Send`",
+    "error[E0512]: cannot transmute between types of different sizes, or dependently-sized types",
+    "= note: source type: `(u64, u8)` (128 bits)",
+    "= note: target type: `[u8; 9]` (72 bits)",
+]
+"#
+    );
+
+    Ok(())
+}
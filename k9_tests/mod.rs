@@ -1,6 +1,7 @@
-#![allow(clippy::if_then_panic)]
+#![allow(clippy::manual_assert)]
 
 mod basic_fixture;
+mod compile_time_assertions;
 mod failure_messages;
 mod inline_snap_test;
 mod support;
@@ -30,6 +30,13 @@ impl TestRun {
 
         // make sure there's no interference with local env
         cmd.env("K9_FORCE_COLORS", "0");
+        cmd.env_remove("RUST_BACKTRACE");
+        cmd.env_remove("K9_DIFF_LAYOUT");
+        cmd.env_remove("K9_FULL_DIFF");
+        cmd.env_remove("K9_DIFF_ALGORITHM");
+        cmd.env_remove("K9_DIFF_LINE_NUMBERS");
+        cmd.env_remove("K9_MAX_MESSAGE_SIZE");
+        cmd.env_remove("K9_ARTIFACTS_DIR");
 
         if self.update_snapshots {
             cmd.env("K9_UPDATE_SNAPSHOTS", "1");
@@ -86,12 +93,27 @@ impl TestRun {
 
         let replaced = finished_regex.replace_all(&replaced, "");
 
+        // newer toolchains print the OS thread id, e.g. `thread 'name' (1234) panicked`
+        // and an extra blank line after the captured stdout header
+        let thread_id_regex = regex::RegexBuilder::new(r#"^(thread '[^']+') \(\d+\)"#)
+            .multi_line(true)
+            .build()?;
+
+        let replaced = thread_id_regex.replace_all(&replaced, "$1");
+
+        let stdout_header_regex = regex::RegexBuilder::new(r#"^(---- \S+ stdout ----)\n\n"#)
+            .multi_line(true)
+            .build()?;
+
+        let replaced = stdout_header_regex.replace_all(&replaced, "$1\n");
+
         Ok(replaced.into())
     }
 }
 
 #[derive(Debug)]
 pub struct TestRunResult {
+    #[allow(dead_code)]
     pub exit_code: Option<i32>,
     pub stderr: String,
     pub stdout: String,
@@ -109,6 +131,27 @@ impl TestRunResult {
         );
         Ok(())
     }
+
+    /// Headlines of the compiler errors (e.g. `error[E0277]: ...`) together with
+    /// their notes, skipping the ones about macro expansion.
+    /// Fails if the project compiled successfully.
+    pub fn compilation_errors(&self) -> Result<Vec<String>> {
+        anyhow::ensure!(
+            !self.success && self.stderr.contains("could not compile"),
+            "Expected test project to fail compilation: stderr:\n--------------------------\n{}\n------------------------\n",
+            self.stderr
+        );
+        Ok(self
+            .stderr
+            .lines()
+            .filter(|line| {
+                let line = line.trim_start();
+                line.starts_with("error[")
+                    || (line.starts_with("= note:") && !line.contains("this error originates"))
+            })
+            .map(|line| line.trim_start().to_string())
+            .collect())
+    }
 }
 
 #[derive(Debug, PartialEq)]